@@ -0,0 +1,376 @@
+//! Derive macros for rust-function-template.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{
+    Attribute, Data, DeriveInput, Expr, ExprLit, Fields, GenericArgument, Lit, LitStr, Meta,
+    PathArguments, Token, Type, parse_macro_input,
+};
+
+/// Derives `CallArguments`, generating the `call_arguments_schema` JSON schema
+/// from the struct's fields.
+///
+/// Fields accept `#[argument(description = "...", minimum = 1, maximum = 200,
+/// default = 20, enum = ["a", "b"])]`. Doc comments are used as the
+/// description when none is given. `Option` fields, fields with a default and
+/// fields serde fills in through `#[serde(default)]` are not required. On a
+/// `Vec` field, `enum` restricts each element. Property names follow
+/// `#[serde(rename)]` and `#[serde(rename_all)]`, so the schema matches what
+/// `Deserialize` expects.
+#[proc_macro_derive(CallArguments, attributes(argument))]
+pub fn derive_call_arguments(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[derive(Default)]
+struct ArgumentAttrs {
+    description: Option<LitStr>,
+    minimum: Option<Expr>,
+    maximum: Option<Expr>,
+    default: Option<Expr>,
+    one_of: Option<Expr>,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.iter().collect(),
+            Fields::Unit => Vec::new(),
+            Fields::Unnamed(_) => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "CallArguments requires a struct with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "CallArguments can only be derived for structs",
+            ));
+        }
+    };
+
+    let rename_all = match serde_rename(&input.attrs, "rename_all")? {
+        Some(rule) => Some(RenameRule::parse(&rule)?),
+        None => None,
+    };
+    let container_default = serde_default(&input.attrs)?;
+
+    let mut properties = Vec::new();
+    let mut required = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let key = match serde_rename(&field.attrs, "rename")? {
+            Some(rename) => rename.value(),
+            None => {
+                let name = ident.to_string().trim_start_matches("r#").to_string();
+                match rename_all {
+                    Some(rule) => rule.apply(&name),
+                    None => name,
+                }
+            }
+        };
+        let attrs = parse_attrs(field)?;
+
+        let (ty, optional) = match option_inner(&field.ty) {
+            Some(inner) => (inner, true),
+            None => (&field.ty, false),
+        };
+        if !optional
+            && attrs.default.is_none()
+            && !container_default
+            && !serde_default(&field.attrs)?
+        {
+            required.push(key.clone());
+        }
+
+        let mut entries = vec![match (&attrs.one_of, generic_inner(ty, "Vec")) {
+            (Some(values), Some(_)) => array_schema(enum_schema(values)),
+            (Some(values), None) => enum_schema(values),
+            (None, _) => type_schema(ty),
+        }];
+        if let Some(description) = attrs.description.or_else(|| doc_comment(field)) {
+            entries.push(quote! { schema.insert("description".to_string(), ::serde_json::json!(#description)); });
+        }
+        for (keyword, expr) in [
+            ("minimum", &attrs.minimum),
+            ("maximum", &attrs.maximum),
+            ("default", &attrs.default),
+        ] {
+            if let Some(expr) = expr {
+                entries.push(
+                    quote! { schema.insert(#keyword.to_string(), ::serde_json::json!(#expr)); },
+                );
+            }
+        }
+        properties.push(quote! {
+            {
+                let mut schema = ::serde_json::Map::new();
+                #(#entries)*
+                properties.insert(#key.to_string(), ::serde_json::Value::Object(schema));
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::rust_function_template::arguments::CallArguments for #name #ty_generics #where_clause {
+            fn schema() -> ::serde_json::Value {
+                let mut properties = ::serde_json::Map::new();
+                #(#properties)*
+                ::serde_json::json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": [#(#required),*],
+                })
+            }
+        }
+    })
+}
+
+/// Deserialize-side value of `#[serde(<key> = "...")]` or
+/// `#[serde(<key>(deserialize = "..."))]`, if present.
+fn serde_rename(attrs: &[Attribute], key: &str) -> syn::Result<Option<LitStr>> {
+    let mut rename = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        let metas = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        for meta in metas.iter().filter(|m| m.path().is_ident(key)) {
+            match meta {
+                Meta::NameValue(nv) => rename = Some(lit_str(&nv.value)?),
+                Meta::List(list) => {
+                    let nested =
+                        list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+                    for side in &nested {
+                        if let Meta::NameValue(nv) = side
+                            && nv.path.is_ident("deserialize")
+                        {
+                            rename = Some(lit_str(&nv.value)?);
+                        }
+                    }
+                }
+                Meta::Path(_) => {
+                    return Err(syn::Error::new_spanned(meta, "expected a string value"));
+                }
+            }
+        }
+    }
+    Ok(rename)
+}
+
+/// Whether the attributes include `#[serde(default)]` or
+/// `#[serde(default = "...")]`.
+fn serde_default(attrs: &[Attribute]) -> syn::Result<bool> {
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        let metas = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        if metas.iter().any(|m| m.path().is_ident("default")) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn lit_str(expr: &Expr) -> syn::Result<LitStr> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) => Ok(s.clone()),
+        _ => Err(syn::Error::new_spanned(expr, "expected a string literal")),
+    }
+}
+
+/// The case conventions of serde's `rename_all`.
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(rule: &LitStr) -> syn::Result<Self> {
+        Ok(match rule.value().as_str() {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => return Err(syn::Error::new_spanned(rule, "unknown rename_all rule")),
+        })
+    }
+
+    /// Renames a snake_case field name, as serde does for struct fields.
+    fn apply(self, field: &str) -> String {
+        let capitalize = |word: &str| {
+            let mut chars = word.chars();
+            chars.next().map_or(String::new(), |c| {
+                c.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        };
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_string(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => field.split('_').map(capitalize).collect(),
+            RenameRule::Camel => {
+                let pascal: String = field.split('_').map(capitalize).collect();
+                let mut chars = pascal.chars();
+                chars.next().map_or(String::new(), |c| {
+                    c.to_ascii_lowercase().to_string() + chars.as_str()
+                })
+            }
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.replace('_', "-").to_ascii_uppercase(),
+        }
+    }
+}
+
+fn parse_attrs(field: &syn::Field) -> syn::Result<ArgumentAttrs> {
+    let mut attrs = ArgumentAttrs::default();
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("argument")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("description") {
+                attrs.description = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("minimum") {
+                attrs.minimum = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("maximum") {
+                attrs.maximum = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("default") {
+                attrs.default = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("enum") {
+                attrs.one_of = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("unknown argument attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(attrs)
+}
+
+fn doc_comment(field: &syn::Field) -> Option<LitStr> {
+    let lines: Vec<String> = field
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => Some(s.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    Some(LitStr::new(
+        &lines.join(" "),
+        proc_macro2::Span::call_site(),
+    ))
+}
+
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+        _ => None,
+    }
+}
+
+fn generic_inner(ty: &Type, wrapper: &str) -> Option<&Type> {
+    let segment = last_segment(ty)?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(GenericArgument::Type(inner)) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn option_inner(ty: &Type) -> Option<&Type> {
+    generic_inner(ty, "Option")
+}
+
+/// Emits the `type` (and `items` for `Vec`) entry for a field type. Numbers and
+/// booleans also accept strings, matching how the platform forwards form input.
+fn type_schema(ty: &Type) -> TokenStream2 {
+    if let Some(item) = generic_inner(ty, "Vec") {
+        return array_schema(type_schema(item));
+    }
+    let json_type = match last_segment(ty).map(|s| s.ident.to_string()).as_deref() {
+        Some(
+            "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+            | "usize",
+        ) => quote! { ["integer", "string"] },
+        Some("f32" | "f64" | "Decimal") => quote! { ["number", "string"] },
+        Some("bool") => quote! { ["boolean", "string"] },
+        Some("String" | "str" | "char") => quote! { "string" },
+        _ => quote! { "object" },
+    };
+    quote! { schema.insert("type".to_string(), ::serde_json::json!(#json_type)); }
+}
+
+/// Emits an array `type` with the entries of `items` as the item schema.
+fn array_schema(items: TokenStream2) -> TokenStream2 {
+    quote! {
+        let items = {
+            let mut schema = ::serde_json::Map::new();
+            #items
+            ::serde_json::Value::Object(schema)
+        };
+        schema.insert("type".to_string(), ::serde_json::json!("array"));
+        schema.insert("items".to_string(), items);
+    }
+}
+
+/// Emits the `enum` entry and a `type` inferred from the listed values rather
+/// than the field type, so string-valued Rust enums are described as strings.
+/// The type falls back to `"string"`.
+fn enum_schema(values: &Expr) -> TokenStream2 {
+    let literals: Vec<&Lit> = match values {
+        Expr::Array(array) => array
+            .elems
+            .iter()
+            .filter_map(|elem| match elem {
+                Expr::Lit(ExprLit { lit, .. }) => Some(lit),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let all = |f: fn(&Lit) -> bool| !literals.is_empty() && literals.iter().all(|lit| f(lit));
+    let json_type = if all(|lit| matches!(lit, Lit::Int(_))) {
+        quote! { ["integer", "string"] }
+    } else if all(|lit| matches!(lit, Lit::Int(_) | Lit::Float(_))) {
+        quote! { ["number", "string"] }
+    } else if all(|lit| matches!(lit, Lit::Bool(_))) {
+        quote! { ["boolean", "string"] }
+    } else {
+        quote! { "string" }
+    };
+    quote! {
+        schema.insert("type".to_string(), ::serde_json::json!(#json_type));
+        schema.insert("enum".to_string(), ::serde_json::json!(#values));
+    }
+}
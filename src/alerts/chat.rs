@@ -0,0 +1,227 @@
+//! Alert payloads for chat platform webhooks: Slack Block Kit, Discord
+//! embeds, Microsoft Teams adaptive cards and Telegram `sendMessage`.
+//!
+//! Functions usually take the format as a call argument and pass it to
+//! [`ChatFormat::from_argument`].
+
+use serde_json::{Value, json};
+
+use super::Alert;
+use super::template::{self, format_timestamp};
+use crate::error::FunctionError;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ChatFormat {
+    /// The structured payload from [`template::webhook_payload`].
+    #[default]
+    Json,
+    Slack,
+    Discord,
+    Teams,
+    Telegram {
+        chat_id: String,
+    },
+}
+
+impl ChatFormat {
+    /// Parses `json`, `slack`, `discord`, `teams` or `telegram`; Telegram
+    /// also needs the chat to post to.
+    pub fn from_argument(
+        name: &str,
+        telegram_chat_id: Option<&str>,
+    ) -> Result<Self, FunctionError> {
+        let invalid = |message: String| FunctionError::InvalidArgument {
+            argument: Some("chat_format".to_string()),
+            message,
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(ChatFormat::Json),
+            "slack" => Ok(ChatFormat::Slack),
+            "discord" => Ok(ChatFormat::Discord),
+            "teams" => Ok(ChatFormat::Teams),
+            "telegram" => telegram_chat_id
+                .map(|chat_id| ChatFormat::Telegram {
+                    chat_id: chat_id.to_string(),
+                })
+                .ok_or_else(|| invalid("telegram requires a chat id".to_string())),
+            other => Err(invalid(format!("unknown chat format `{other}`"))),
+        }
+    }
+
+    pub fn render(&self, alert: &Alert) -> Value {
+        match self {
+            ChatFormat::Json => template::webhook_payload(alert),
+            ChatFormat::Slack => slack(alert),
+            ChatFormat::Discord => discord(alert),
+            ChatFormat::Teams => teams(alert),
+            ChatFormat::Telegram { chat_id } => telegram(alert, chat_id),
+        }
+    }
+}
+
+/// Discord embed sidebar color.
+const DISCORD_COLOR: u32 = 0x3498db;
+
+/// Price, time and indicator values shown as labelled fields.
+fn facts(alert: &Alert) -> Vec<(String, String)> {
+    let mut facts = vec![
+        ("Price".to_string(), alert.price.to_string()),
+        ("Time".to_string(), format_timestamp(alert.timestamp)),
+    ];
+    facts.extend(
+        alert
+            .indicators
+            .iter()
+            .map(|(name, value)| (name.clone(), value.to_string())),
+    );
+    facts
+}
+
+fn escape(value: &str, entities: &[(char, &str)]) -> String {
+    value
+        .chars()
+        .map(|c| match entities.iter().find(|(e, _)| *e == c) {
+            Some((_, replacement)) => replacement.to_string(),
+            None => c.to_string(),
+        })
+        .collect()
+}
+
+fn slack_escape(value: &str) -> String {
+    escape(value, &[('&', "&amp;"), ('<', "&lt;"), ('>', "&gt;")])
+}
+
+/// Backslash-escapes the characters Discord markdown would format.
+fn discord_escape(value: &str) -> String {
+    escape(
+        value,
+        &[
+            ('\\', "\\\\"),
+            ('*', "\\*"),
+            ('_', "\\_"),
+            ('`', "\\`"),
+            ('~', "\\~"),
+            ('|', "\\|"),
+        ],
+    )
+}
+
+fn slack(alert: &Alert) -> Value {
+    let fields: Vec<Value> = facts(alert)
+        .iter()
+        .map(|(name, value)| {
+            json!({
+                "type": "mrkdwn",
+                "text": format!("*{}*\n{}", slack_escape(name), slack_escape(value)),
+            })
+        })
+        .collect();
+    json!({
+        "text": slack_escape(&alert.message()),
+        "blocks": [
+            {
+                "type": "header",
+                "text": { "type": "plain_text", "text": alert.rule },
+            },
+            {
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": format!("*{}* {}", slack_escape(&alert.label), slack_escape(&alert.condition)),
+                },
+            },
+            { "type": "section", "fields": fields },
+        ],
+    })
+}
+
+fn discord(alert: &Alert) -> Value {
+    let fields: Vec<Value> = facts(alert)
+        .into_iter()
+        .map(|(name, value)| {
+            json!({
+                "name": discord_escape(&name),
+                "value": discord_escape(&value),
+                "inline": true,
+            })
+        })
+        .collect();
+    json!({
+        "embeds": [
+            {
+                "title": discord_escape(&alert.rule),
+                "description": format!(
+                    "**{}** {}",
+                    discord_escape(&alert.label),
+                    discord_escape(&alert.condition)
+                ),
+                "color": DISCORD_COLOR,
+                "timestamp": format_timestamp(alert.timestamp),
+                "fields": fields,
+            },
+        ],
+    })
+}
+
+fn teams(alert: &Alert) -> Value {
+    let facts: Vec<Value> = facts(alert)
+        .into_iter()
+        .map(|(title, value)| json!({ "title": title, "value": value }))
+        .collect();
+    json!({
+        "type": "message",
+        "attachments": [
+            {
+                "contentType": "application/vnd.microsoft.card.adaptive",
+                "contentUrl": null,
+                "content": {
+                    "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                    "type": "AdaptiveCard",
+                    "version": "1.4",
+                    "body": [
+                        {
+                            "type": "TextBlock",
+                            "size": "Medium",
+                            "weight": "Bolder",
+                            "text": alert.rule,
+                        },
+                        {
+                            "type": "TextBlock",
+                            "text": format!("{} {}", alert.label, alert.condition),
+                            "wrap": true,
+                        },
+                        { "type": "FactSet", "facts": facts },
+                    ],
+                },
+            },
+        ],
+    })
+}
+
+fn telegram(alert: &Alert, chat_id: &str) -> Value {
+    let html = |value: &str| {
+        escape(
+            value,
+            &[
+                ('&', "&amp;"),
+                ('<', "&lt;"),
+                ('>', "&gt;"),
+                ('"', "&quot;"),
+            ],
+        )
+    };
+    let mut lines = vec![
+        format!("<b>{}</b>", html(&alert.rule)),
+        format!("<b>{}</b> {}", html(&alert.label), html(&alert.condition)),
+    ];
+    lines.extend(
+        facts(alert)
+            .iter()
+            .map(|(name, value)| format!("{}: {}", html(name), html(value))),
+    );
+    json!({
+        "chat_id": chat_id,
+        "text": lines.join("\n"),
+        "parse_mode": "HTML",
+    })
+}
@@ -0,0 +1,401 @@
+//! Alert rules evaluated over candle series and dispatched through
+//! [`schedule_webhook`](crate::host::schedule_webhook) and
+//! [`schedule_email`](crate::host::schedule_email).
+//!
+//! Rules are edge-triggered: a rule fires when its condition becomes true,
+//! then stays quiet until the condition has been false again. Whether each
+//! condition held at the last evaluation is kept in [`State`], so a price
+//! sitting above a threshold alerts once rather than on every invocation.
+//!
+//! ```ignore
+//! let engine = AlertEngine::new(vec![
+//!     Rule::new("btc-100k", "btc", Condition::CrossAbove(100_000.0))
+//!         .email("desk@example.com")
+//!         .webhook("/alerts"),
+//! ])?;
+//! let alerts = engine.run(&call_args)?;
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use exchange_outpost_abi::{Candle, FunctionArgs};
+use serde::{Deserialize, Serialize};
+
+use crate::error::FunctionError;
+use crate::state::State;
+use crate::validation::{SeriesIssue, SeriesProblem};
+
+pub mod chat;
+pub mod notifier;
+pub mod signing;
+pub mod template;
+
+use chat::ChatFormat;
+use notifier::{Notifier, NotifierConfig};
+use signing::Signer;
+use template::{EmailTemplate, TemplateContext};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// Close above a fixed level.
+    CrossAbove(f64),
+    /// Close below a fixed level.
+    CrossBelow(f64),
+    /// `fast` above `slow`. Both series are aligned with the rule's candles,
+    /// e.g. two moving averages.
+    SeriesCrossAbove {
+        fast: Vec<Option<f64>>,
+        slow: Vec<Option<f64>>,
+    },
+    /// `fast` below `slow`.
+    SeriesCrossBelow {
+        fast: Vec<Option<f64>>,
+        slow: Vec<Option<f64>>,
+    },
+    /// Close moved at least `percent` (up or down) over the last `bars` candles.
+    PercentMove { percent: f64, bars: usize },
+    /// High above the highest high of the previous `bars` candles.
+    NewHigh { bars: usize },
+    /// Low below the lowest low of the previous `bars` candles.
+    NewLow { bars: usize },
+}
+
+impl Condition {
+    /// Whether the condition holds at candle `index`; `None` without enough
+    /// history or with a lookback of zero candles.
+    pub fn holds_at(&self, candles: &[Candle<f64>], index: usize) -> Option<bool> {
+        let candle = candles.get(index)?;
+        if self.bars() == Some(0) {
+            return None;
+        }
+        match self {
+            Condition::CrossAbove(level) => Some(candle.close > *level),
+            Condition::CrossBelow(level) => Some(candle.close < *level),
+            Condition::SeriesCrossAbove { fast, slow } => {
+                Some((*fast.get(index)?)? > (*slow.get(index)?)?)
+            }
+            Condition::SeriesCrossBelow { fast, slow } => {
+                Some((*fast.get(index)?)? < (*slow.get(index)?)?)
+            }
+            Condition::PercentMove { percent, bars } => {
+                let base = candles.get(index.checked_sub(*bars)?)?.close;
+                if base == 0.0 {
+                    return None;
+                }
+                Some(((candle.close - base) / base * 100.0).abs() >= *percent)
+            }
+            Condition::NewHigh { bars } => {
+                let window = &candles[index.checked_sub(*bars)?..index];
+                Some(window.iter().all(|c| candle.high > c.high))
+            }
+            Condition::NewLow { bars } => {
+                let window = &candles[index.checked_sub(*bars)?..index];
+                Some(window.iter().all(|c| candle.low < c.low))
+            }
+        }
+    }
+
+    /// Lookback of the conditions that compare against earlier candles.
+    fn bars(&self) -> Option<usize> {
+        match self {
+            Condition::PercentMove { bars, .. }
+            | Condition::NewHigh { bars }
+            | Condition::NewLow { bars } => Some(*bars),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::CrossAbove(level) => write!(f, "crossed above {level}"),
+            Condition::CrossBelow(level) => write!(f, "crossed below {level}"),
+            Condition::SeriesCrossAbove { .. } => {
+                write!(f, "fast series crossed above slow series")
+            }
+            Condition::SeriesCrossBelow { .. } => {
+                write!(f, "fast series crossed below slow series")
+            }
+            Condition::PercentMove { percent, bars } => {
+                write!(f, "moved {percent}% within {bars} candles")
+            }
+            Condition::NewHigh { bars } => write!(f, "made a new {bars}-candle high"),
+            Condition::NewLow { bars } => write!(f, "made a new {bars}-candle low"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dispatch {
+    /// Posts the alert to `path`, rendered in `format`.
+    Webhook {
+        path: String,
+        format: ChatFormat,
+    },
+    Email {
+        to: String,
+        template: EmailTemplate,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub name: String,
+    /// Financial data label whose candles the rule watches.
+    pub label: String,
+    pub condition: Condition,
+    pub dispatch: Vec<Dispatch>,
+    /// Named series aligned with the candles whose latest values are
+    /// attached to alerts, e.g. `("rsi", rsi_values)`.
+    pub indicators: Vec<(String, Vec<Option<f64>>)>,
+}
+
+impl Rule {
+    pub fn new(name: &str, label: &str, condition: Condition) -> Self {
+        Self {
+            name: name.to_string(),
+            label: label.to_string(),
+            condition,
+            dispatch: Vec::new(),
+            indicators: Vec::new(),
+        }
+    }
+
+    pub fn indicator(mut self, name: &str, values: Vec<Option<f64>>) -> Self {
+        self.indicators.push((name.to_string(), values));
+        self
+    }
+
+    /// Posts the structured JSON payload to `path`.
+    pub fn webhook(self, path: &str) -> Self {
+        self.webhook_as(path, ChatFormat::Json)
+    }
+
+    /// Posts the alert to `path` rendered for a chat platform.
+    pub fn webhook_as(mut self, path: &str, format: ChatFormat) -> Self {
+        self.dispatch.push(Dispatch::Webhook {
+            path: path.to_string(),
+            format,
+        });
+        self
+    }
+
+    pub fn email(self, to: &str) -> Self {
+        self.email_with(to, EmailTemplate::default())
+    }
+
+    pub fn email_with(mut self, to: &str, template: EmailTemplate) -> Self {
+        self.dispatch.push(Dispatch::Email {
+            to: to.to_string(),
+            template,
+        });
+        self
+    }
+}
+
+/// A rule that fired on the latest candle of its series.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Alert {
+    pub rule: String,
+    pub label: String,
+    pub condition: String,
+    pub timestamp: i64,
+    pub price: f64,
+    /// Latest value of each indicator attached to the rule.
+    pub indicators: BTreeMap<String, f64>,
+}
+
+impl Alert {
+    /// Plain-text summary rendered from [`template::DEFAULT_TEXT`].
+    pub fn message(&self) -> String {
+        template::render(
+            template::DEFAULT_TEXT,
+            &TemplateContext::from_alert(self),
+            false,
+        )
+        .expect("default template only uses alert placeholders")
+    }
+}
+
+/// Whether each rule's condition held at its last evaluation, by rule name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlertMemory {
+    pub active: HashMap<String, bool>,
+}
+
+pub struct AlertEngine {
+    rules: Vec<Rule>,
+    state: State<AlertMemory>,
+    notifier: NotifierConfig,
+    signer: Option<Signer>,
+}
+
+impl AlertEngine {
+    /// Fails when two rules share a name, since the edge-trigger state and
+    /// notification history are kept per rule name, and when a condition
+    /// looks back zero candles.
+    pub fn new(rules: Vec<Rule>) -> Result<Self, FunctionError> {
+        for (i, rule) in rules.iter().enumerate() {
+            let problem = if rules[..i].iter().any(|other| other.name == rule.name) {
+                Some(format!("duplicate alert rule name `{}`", rule.name))
+            } else if rule.condition.bars() == Some(0) {
+                Some(format!(
+                    "alert rule `{}` must look back at least one candle",
+                    rule.name
+                ))
+            } else {
+                None
+            };
+            if let Some(message) = problem {
+                return Err(FunctionError::InvalidArgument {
+                    argument: None,
+                    message,
+                });
+            }
+        }
+        Ok(Self {
+            rules,
+            state: State::new("alerts", 1),
+            notifier: NotifierConfig::default(),
+            signer: None,
+        })
+    }
+
+    /// Wraps [`ChatFormat::Json`] webhook payloads in a signed envelope.
+    /// Chat formats are sent unsigned, since chat platforms only accept their
+    /// own payload shape. Pass
+    /// `Signer::from_config(signing::DEFAULT_CONFIG_KEY)?` to sign only when
+    /// a secret is configured.
+    pub fn with_signer(mut self, signer: Option<Signer>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    /// Applies de-duplication, cooldowns and rate limits to dispatched alerts.
+    pub fn with_notifier(mut self, config: NotifierConfig) -> Self {
+        self.notifier = config;
+        self
+    }
+
+    /// Uses a different state key, for functions running several engines.
+    pub fn with_state_key(mut self, key: &str) -> Self {
+        self.state = State::new(key, 1);
+        self
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Evaluates every rule, dispatches the alerts that fired and persists
+    /// the edge-trigger state. Fails with [`FunctionError::MissingData`]
+    /// before evaluating anything when a rule's label is not in the call data.
+    ///
+    /// The edge-trigger state is only saved once every alert was dispatched,
+    /// so a failed dispatch fires again on the next run. The notification
+    /// history is saved either way, so when the retry evaluates the same
+    /// candle, targets that were already reached are de-duplicated rather
+    /// than notified twice. A retry after a newer candle arrived is a new
+    /// notification unless `dedup_window` covers it.
+    pub fn run(&self, call_args: &FunctionArgs) -> Result<Vec<Alert>, FunctionError> {
+        let mut series = HashMap::new();
+        let mut problems = Vec::new();
+        for rule in &self.rules {
+            if series.contains_key(&rule.label)
+                || problems
+                    .iter()
+                    .any(|p: &SeriesProblem| p.label == rule.label)
+            {
+                continue;
+            }
+            match call_args.get_candles(&rule.label) {
+                Ok(candles) => {
+                    series.insert(rule.label.clone(), candles);
+                }
+                Err(_) => problems.push(SeriesProblem {
+                    label: rule.label.clone(),
+                    issue: SeriesIssue::Missing,
+                }),
+            }
+        }
+        if !problems.is_empty() {
+            return Err(FunctionError::MissingData { problems });
+        }
+
+        let mut memory = self.state.load_or_default()?;
+        let mut alerts = Vec::new();
+        for rule in &self.rules {
+            if let Some(alert) = evaluate(rule, &series[&rule.label], &mut memory) {
+                alerts.push(alert);
+            }
+        }
+
+        let mut notifier =
+            Notifier::load_with_key(self.notifier, &format!("{}.notifier", self.state.key()))?;
+        let dispatched = alerts
+            .iter()
+            .try_for_each(|alert| self.dispatch(&mut notifier, alert));
+        notifier.save()?;
+        dispatched?;
+        self.state.save(&memory)?;
+        Ok(alerts)
+    }
+
+    fn dispatch(&self, notifier: &mut Notifier, alert: &Alert) -> Result<(), FunctionError> {
+        let Some(rule) = self.rules.iter().find(|r| r.name == alert.rule) else {
+            return Ok(());
+        };
+        for target in &rule.dispatch {
+            match target {
+                Dispatch::Webhook { path, format } => {
+                    let mut payload = format.render(alert).to_string();
+                    if let (Some(signer), ChatFormat::Json) = (&self.signer, format) {
+                        payload = signer.sign(&payload, alert.timestamp);
+                    }
+                    notifier.webhook(
+                        &rule.name,
+                        &alert.condition,
+                        alert.timestamp,
+                        path,
+                        &payload,
+                    )?;
+                }
+                Dispatch::Email { to, template } => {
+                    let body = template.render(&TemplateContext::from_alert(alert))?;
+                    notifier.email(&rule.name, &alert.condition, alert.timestamp, to, &body)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Evaluates `rule` at the last candle, firing on a false-to-true transition.
+/// Without memory for the rule, the previous candle decides whether the
+/// condition was already active.
+pub fn evaluate(rule: &Rule, candles: &[Candle<f64>], memory: &mut AlertMemory) -> Option<Alert> {
+    let last = candles.len().checked_sub(1)?;
+    let now = rule.condition.holds_at(candles, last)?;
+    let before = memory
+        .active
+        .insert(rule.name.clone(), now)
+        .unwrap_or_else(|| {
+            last.checked_sub(1)
+                .and_then(|prev| rule.condition.holds_at(candles, prev))
+                .unwrap_or(false)
+        });
+    (now && !before).then(|| Alert {
+        rule: rule.name.clone(),
+        label: rule.label.clone(),
+        condition: rule.condition.to_string(),
+        timestamp: candles[last].timestamp,
+        price: candles[last].close,
+        indicators: rule
+            .indicators
+            .iter()
+            .filter_map(|(name, values)| Some((name.clone(), (*values.get(last)?)?)))
+            .collect(),
+    })
+}
@@ -0,0 +1,187 @@
+//! De-duplication, cooldowns and rate limiting in front of
+//! [`schedule_webhook`] and [`schedule_email`].
+//!
+//! Times are candle timestamps (the function has no wall clock), so the
+//! windows below use the same unit as the timestamps of the series.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::FunctionError;
+use crate::host::{schedule_email, schedule_webhook};
+use crate::state::State;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotifierConfig {
+    /// Minimum time between two notifications of the same rule to the same target.
+    pub cooldown: i64,
+    /// Repeats of a notification (same rule, target and condition) within
+    /// this window are dropped, even when the rendered content differs.
+    /// Repeats at the same timestamp, such as a retried run on the same
+    /// candle, are dropped regardless of the window.
+    pub dedup_window: i64,
+    /// At most `max_per_window` notifications are sent in any `window`.
+    pub max_per_window: usize,
+    pub window: i64,
+}
+
+impl Default for NotifierConfig {
+    fn default() -> Self {
+        Self {
+            cooldown: 0,
+            dedup_window: 0,
+            max_per_window: usize::MAX,
+            window: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Delivery {
+    Sent,
+    Duplicate,
+    CoolingDown,
+    RateLimited,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SentRecord {
+    pub rule: String,
+    pub target: String,
+    pub fingerprint: String,
+    pub timestamp: i64,
+}
+
+/// Notifications sent recently enough to matter for the configured windows.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotifierMemory {
+    pub sent: Vec<SentRecord>,
+}
+
+pub struct Notifier {
+    config: NotifierConfig,
+    state: State<NotifierMemory>,
+    memory: NotifierMemory,
+}
+
+impl Notifier {
+    pub fn load(config: NotifierConfig) -> Result<Self, FunctionError> {
+        Self::load_with_key(config, "notifier")
+    }
+
+    pub fn load_with_key(config: NotifierConfig, key: &str) -> Result<Self, FunctionError> {
+        let state = State::new(key, 1);
+        let memory = state.load_or_default()?;
+        Ok(Self {
+            config,
+            state,
+            memory,
+        })
+    }
+
+    /// Schedules `payload` to `path` unless a window suppresses it.
+    /// `condition` describes what triggered the notification and, with the
+    /// rule and target, identifies repeats for de-duplication.
+    pub fn webhook(
+        &mut self,
+        rule: &str,
+        condition: &str,
+        now: i64,
+        path: &str,
+        payload: &str,
+    ) -> Result<Delivery, FunctionError> {
+        let target = format!("webhook:{path}");
+        let delivery = self.check(rule, condition, now, &target);
+        if delivery == Delivery::Sent {
+            schedule_webhook(path, payload)?;
+            self.record(rule, condition, now, target);
+        }
+        Ok(delivery)
+    }
+
+    /// Schedules an email with `body` to `to`; see [`Notifier::webhook`].
+    pub fn email(
+        &mut self,
+        rule: &str,
+        condition: &str,
+        now: i64,
+        to: &str,
+        body: &str,
+    ) -> Result<Delivery, FunctionError> {
+        let target = format!("email:{to}");
+        let delivery = self.check(rule, condition, now, &target);
+        if delivery == Delivery::Sent {
+            schedule_email(to, body)?;
+            self.record(rule, condition, now, target);
+        }
+        Ok(delivery)
+    }
+
+    /// Persists the history, dropping records older than every window. Records
+    /// at the latest timestamp are kept to catch a retried run.
+    pub fn save(&mut self) -> Result<(), FunctionError> {
+        let retention = self
+            .config
+            .cooldown
+            .max(self.config.dedup_window)
+            .max(self.config.window);
+        if let Some(latest) = self.memory.sent.iter().map(|r| r.timestamp).max() {
+            self.memory
+                .sent
+                .retain(|r| r.timestamp == latest || latest - r.timestamp < retention);
+        }
+        self.state.save(&self.memory)
+    }
+
+    fn check(&self, rule: &str, condition: &str, now: i64, target: &str) -> Delivery {
+        let fingerprint = fingerprint(rule, target, condition);
+        let within = |record: &SentRecord, window: i64| now - record.timestamp < window;
+        let sent = &self.memory.sent;
+        if sent.iter().any(|r| {
+            r.fingerprint == fingerprint
+                && (r.timestamp == now || within(r, self.config.dedup_window))
+        }) {
+            Delivery::Duplicate
+        } else if sent
+            .iter()
+            .any(|r| r.rule == rule && r.target == target && within(r, self.config.cooldown))
+        {
+            Delivery::CoolingDown
+        } else if sent
+            .iter()
+            .filter(|r| within(r, self.config.window))
+            .count()
+            >= self.config.max_per_window
+        {
+            Delivery::RateLimited
+        } else {
+            Delivery::Sent
+        }
+    }
+
+    fn record(&mut self, rule: &str, condition: &str, now: i64, target: String) {
+        self.memory.sent.push(SentRecord {
+            rule: rule.to_string(),
+            fingerprint: fingerprint(rule, &target, condition),
+            target,
+            timestamp: now,
+        });
+    }
+}
+
+/// FNV-1a of the rule, target and condition, stable across builds so stored
+/// fingerprints stay comparable.
+fn fingerprint(rule: &str, target: &str, condition: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let bytes = rule
+        .bytes()
+        .chain([0])
+        .chain(target.bytes())
+        .chain([0])
+        .chain(condition.bytes());
+    for byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
@@ -0,0 +1,149 @@
+//! HMAC-SHA256 signing of webhook payloads so receivers can verify they come
+//! from this function.
+//!
+//! A signed webhook body is a [`SignedEnvelope`]: the original payload as a
+//! string, the timestamp it was signed at and the hex signature of
+//! `"{timestamp}.{payload}"`. The secret is read from the Extism config, so
+//! it never appears in the function's code or arguments.
+//!
+//! Functions have no wall clock, so the timestamp is the timestamp of the
+//! candle that triggered the alert: the bar's open time, in the unit of the
+//! series (seconds or milliseconds). Receivers checking freshness must use
+//! the same unit and allow for the bar's duration.
+
+use std::fmt;
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::error::FunctionError;
+use crate::host;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Extism config key the secret is read from by default.
+pub const DEFAULT_CONFIG_KEY: &str = "webhook_signing_secret";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedEnvelope {
+    pub payload: String,
+    pub timestamp: i64,
+    /// Lowercase hex HMAC-SHA256 of `"{timestamp}.{payload}"`.
+    pub signature: String,
+}
+
+#[derive(Clone)]
+pub struct Signer {
+    secret: Vec<u8>,
+}
+
+impl fmt::Debug for Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signer").finish_non_exhaustive()
+    }
+}
+
+impl Signer {
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            secret: secret.into(),
+        }
+    }
+
+    /// Signer for the secret under `key` in the Extism config; `None` when
+    /// it is unset or empty, which leaves signing off.
+    pub fn from_config(key: &str) -> Result<Option<Self>, FunctionError> {
+        Ok(host::config_get(key)?
+            .filter(|secret| !secret.is_empty())
+            .map(Signer::new))
+    }
+
+    pub fn signature(&self, payload: &str, timestamp: i64) -> String {
+        let bytes = mac(&self.secret, payload, timestamp)
+            .finalize()
+            .into_bytes();
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    pub fn envelope(&self, payload: &str, timestamp: i64) -> SignedEnvelope {
+        SignedEnvelope {
+            payload: payload.to_string(),
+            timestamp,
+            signature: self.signature(payload, timestamp),
+        }
+    }
+
+    /// The signed envelope serialized as the webhook body.
+    pub fn sign(&self, payload: &str, timestamp: i64) -> String {
+        serde_json::to_string(&self.envelope(payload, timestamp))
+            .expect("envelope serializes to json")
+    }
+}
+
+fn mac(secret: &[u8], payload: &str, timestamp: i64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    Malformed(String),
+    BadSignature,
+    /// The envelope is older (or newer) than the allowed tolerance.
+    Expired {
+        age: i64,
+    },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::Malformed(message) => write!(f, "malformed envelope: {message}"),
+            VerifyError::BadSignature => write!(f, "signature does not match"),
+            VerifyError::Expired { age } => write!(f, "envelope timestamp is {age} away from now"),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Verifies a signed webhook body and returns the original payload. Pass
+/// `tolerance` to also reject envelopes whose timestamp is further than that
+/// from `now`, which limits replays. `now` and `tolerance` are in the unit of
+/// the signed candle timestamps, and `tolerance` must cover at least one bar
+/// since the timestamp is the bar's open time.
+pub fn verify(
+    body: &str,
+    secret: &[u8],
+    now: i64,
+    tolerance: Option<i64>,
+) -> Result<String, VerifyError> {
+    let envelope: SignedEnvelope =
+        serde_json::from_str(body).map_err(|e| VerifyError::Malformed(e.to_string()))?;
+    let signature = decode_hex(&envelope.signature)
+        .ok_or_else(|| VerifyError::Malformed("signature is not hex".to_string()))?;
+    mac(secret, &envelope.payload, envelope.timestamp)
+        .verify_slice(&signature)
+        .map_err(|_| VerifyError::BadSignature)?;
+    if let Some(tolerance) = tolerance {
+        let age = now - envelope.timestamp;
+        if age.abs() > tolerance {
+            return Err(VerifyError::Expired { age });
+        }
+    }
+    Ok(envelope.payload)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,283 @@
+//! Placeholder templates for alert emails and structured webhook payloads.
+//!
+//! Templates use `{{name}}` placeholders. An alert provides `rule`, `symbol`,
+//! `condition`, `price`, `timestamp` and `time` (UTC, ISO 8601), plus
+//! `indicator.<name>` for every indicator attached to its rule. Unknown
+//! placeholders are an error rather than being sent verbatim.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use super::Alert;
+use crate::error::FunctionError;
+
+pub const DEFAULT_TEXT: &str = "{{rule}}: {{symbol}} {{condition}} (price {{price}} at {{time}})";
+pub const DEFAULT_HTML: &str = "<p><strong>{{rule}}</strong>: {{symbol}} {{condition}}</p>\
+<p>Price {{price}} at {{time}}</p>";
+
+/// Values available to a template, by placeholder name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TemplateContext {
+    values: BTreeMap<String, String>,
+}
+
+impl TemplateContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(mut self, name: &str, value: impl fmt::Display) -> Self {
+        self.values.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn indicator(self, name: &str, value: impl fmt::Display) -> Self {
+        self.set(&format!("indicator.{name}"), value)
+    }
+
+    pub fn from_alert(alert: &Alert) -> Self {
+        let mut context = Self::new()
+            .set("rule", &alert.rule)
+            .set("symbol", &alert.label)
+            .set("condition", &alert.condition)
+            .set("price", alert.price)
+            .set("timestamp", alert.timestamp)
+            .set("time", format_timestamp(alert.timestamp));
+        for (name, value) in &alert.indicators {
+            context = context.indicator(name, value);
+        }
+        context
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailFormat {
+    #[default]
+    Text,
+    Html,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmailTemplate {
+    pub text: String,
+    pub html: String,
+    pub format: EmailFormat,
+}
+
+impl Default for EmailTemplate {
+    fn default() -> Self {
+        Self {
+            text: DEFAULT_TEXT.to_string(),
+            html: DEFAULT_HTML.to_string(),
+            format: EmailFormat::Text,
+        }
+    }
+}
+
+impl EmailTemplate {
+    pub fn text(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn html(html: &str) -> Self {
+        Self {
+            html: html.to_string(),
+            format: EmailFormat::Html,
+            ..Self::default()
+        }
+    }
+
+    /// Renders the variant selected by `format`; values are HTML-escaped in the HTML variant.
+    pub fn render(&self, context: &TemplateContext) -> Result<String, FunctionError> {
+        match self.format {
+            EmailFormat::Text => render(&self.text, context, false),
+            EmailFormat::Html => render(&self.html, context, true),
+        }
+    }
+}
+
+/// Replaces every `{{name}}` in `template` with its value from `context`.
+pub fn render(
+    template: &str,
+    context: &TemplateContext,
+    escape_html: bool,
+) -> Result<String, FunctionError> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| template_error("unclosed `{{`"))?;
+        let name = after[..end].trim();
+        let value = context
+            .get(name)
+            .ok_or_else(|| template_error(&format!("unknown placeholder `{name}`")))?;
+        if escape_html {
+            out.push_str(&html_escape(value));
+        } else {
+            out.push_str(value);
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn template_error(message: &str) -> FunctionError {
+    FunctionError::InvalidArgument {
+        argument: Some("template".to_string()),
+        message: message.to_string(),
+    }
+}
+
+fn html_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Structured JSON body for an alert webhook.
+pub fn webhook_payload(alert: &Alert) -> Value {
+    json!({
+        "type": "alert",
+        "rule": alert.rule,
+        "symbol": alert.label,
+        "condition": alert.condition,
+        "price": alert.price,
+        "timestamp": alert.timestamp,
+        "time": format_timestamp(alert.timestamp),
+        "indicators": alert.indicators,
+        "message": alert.message(),
+    })
+}
+
+/// Formats a Unix timestamp as UTC ISO 8601. Values above 10^11 in absolute
+/// value are taken as milliseconds, anything else as seconds. Millisecond
+/// timestamps within 10^11 of the epoch (1973-03-03T09:46:40Z, mirrored
+/// before 1970) are therefore read as seconds, and second timestamps past
+/// the year 5138 as milliseconds.
+pub fn format_timestamp(timestamp: i64) -> String {
+    let seconds = if timestamp.abs() > 100_000_000_000 {
+        timestamp.div_euclid(1000)
+    } else {
+        timestamp
+    };
+    let days = seconds.div_euclid(86_400);
+    let time = seconds.rem_euclid(86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> TemplateContext {
+        TemplateContext::new()
+            .set("rule", "a<b & \"c\"")
+            .set("symbol", "<btc>")
+            .indicator("rsi", 71.5)
+    }
+
+    fn message(err: FunctionError) -> String {
+        match err {
+            FunctionError::InvalidArgument {
+                argument: Some(argument),
+                message,
+            } if argument == "template" => message,
+            other => panic!("unexpected error {other:?}"),
+        }
+    }
+
+    #[test]
+    fn html_variant_escapes_values() {
+        let template = EmailTemplate::html("<b>{{rule}}</b> on {{symbol}}");
+        assert_eq!(
+            template.render(&context()).unwrap(),
+            "<b>a&lt;b &amp; &quot;c&quot;</b> on &lt;btc&gt;"
+        );
+        let template = EmailTemplate::text("{{rule}} on {{symbol}}");
+        assert_eq!(template.render(&context()).unwrap(), "a<b & \"c\" on <btc>");
+    }
+
+    #[test]
+    fn substitutes_indicators() {
+        assert_eq!(
+            render(
+                "RSI {{indicator.rsi}}, {{ indicator.rsi }}",
+                &context(),
+                false
+            )
+            .unwrap(),
+            "RSI 71.5, 71.5"
+        );
+    }
+
+    #[test]
+    fn unknown_placeholder_is_an_error() {
+        let err = render("{{indicator.macd}}", &context(), false).unwrap_err();
+        assert_eq!(message(err), "unknown placeholder `indicator.macd`");
+    }
+
+    #[test]
+    fn unclosed_placeholder_is_an_error() {
+        let err = render("{{rule}} at {{time", &context(), false).unwrap_err();
+        assert_eq!(message(err), "unclosed `{{`");
+    }
+
+    #[test]
+    fn formats_seconds_and_milliseconds() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14T22:13:20Z");
+        assert_eq!(format_timestamp(1_700_000_000_123), "2023-11-14T22:13:20Z");
+        assert_eq!(format_timestamp(951_868_799), "2000-02-29T23:59:59Z");
+        // At the threshold the value is still read as seconds.
+        assert_eq!(format_timestamp(100_000_000_000), "5138-11-16T09:46:40Z");
+    }
+
+    #[test]
+    fn formats_negative_timestamps() {
+        assert_eq!(format_timestamp(-1), "1969-12-31T23:59:59Z");
+        assert_eq!(format_timestamp(-1_700_000_000), "1916-02-18T01:46:40Z");
+        // Milliseconds round towards the earlier second.
+        assert_eq!(format_timestamp(-1_700_000_000_500), "1916-02-18T01:46:39Z");
+    }
+}
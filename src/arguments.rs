@@ -0,0 +1,190 @@
+//! Typed call arguments. Derive [`CallArguments`] on a `Deserialize` struct to
+//! get both the manifest's `call_arguments_schema` and validated parsing of
+//! the arguments passed to `run`.
+//!
+//! ```ignore
+//! #[derive(Deserialize, CallArguments)]
+//! pub struct Arguments {
+//!     /// Number of periods for moving average calculation
+//!     #[argument(minimum = 1, maximum = 200, default = 20)]
+//!     pub period: u32,
+//!     #[argument(enum = ["sma", "ema"], default = "sma")]
+//!     pub kind: String,
+//! }
+//! ```
+
+use std::fmt;
+
+use exchange_outpost_abi::FunctionArgs;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+pub use rust_function_template_macros::CallArguments;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArgumentError {
+    pub argument: Option<String>,
+    pub message: String,
+}
+
+impl ArgumentError {
+    fn new(argument: &str, message: impl Into<String>) -> Self {
+        Self {
+            argument: Some(argument.to_string()),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ArgumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.argument {
+            Some(argument) => write!(f, "invalid argument `{argument}`: {}", self.message),
+            None => write!(f, "invalid arguments: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ArgumentError {}
+
+pub trait CallArguments: DeserializeOwned {
+    /// JSON schema for the manifest's `call_arguments_schema`.
+    fn schema() -> Value;
+
+    /// Reads the call arguments, applying schema defaults, coercing string
+    /// input for numeric and boolean arguments, and checking `enum`,
+    /// `minimum` and `maximum` before deserializing.
+    fn from_call_args(call_args: &FunctionArgs) -> Result<Self, ArgumentError> {
+        let value =
+            serde_json::to_value(call_args.get_call_arguments()).map_err(|e| ArgumentError {
+                argument: None,
+                message: e.to_string(),
+            })?;
+        Self::from_value(value)
+    }
+
+    fn from_value(value: Value) -> Result<Self, ArgumentError> {
+        let mut arguments = match value {
+            Value::Object(map) => map,
+            Value::Null => Map::new(),
+            _ => {
+                return Err(ArgumentError {
+                    argument: None,
+                    message: "call arguments must be an object".to_string(),
+                });
+            }
+        };
+        apply_schema(&Self::schema(), &mut arguments)?;
+        serde_json::from_value(Value::Object(arguments)).map_err(|e| ArgumentError {
+            argument: None,
+            message: e.to_string(),
+        })
+    }
+}
+
+fn apply_schema(schema: &Value, arguments: &mut Map<String, Value>) -> Result<(), ArgumentError> {
+    let Some(properties) = schema["properties"].as_object() else {
+        return Ok(());
+    };
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    for (name, property) in properties {
+        match arguments.get_mut(name) {
+            Some(value) if !value.is_null() => {
+                coerce(name, property, value)?;
+                check_constraints(name, property, value)?;
+            }
+            _ => {
+                if let Some(default) = property.get("default") {
+                    arguments.insert(name.clone(), default.clone());
+                } else if required.contains(&name.as_str()) {
+                    return Err(ArgumentError::new(name, "missing required argument"));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn accepts(property: &Value, json_type: &str) -> bool {
+    match &property["type"] {
+        Value::String(t) => t == json_type,
+        Value::Array(types) => types.iter().any(|t| t == json_type),
+        _ => false,
+    }
+}
+
+fn coerce(name: &str, property: &Value, value: &mut Value) -> Result<(), ArgumentError> {
+    if let Value::Array(items) = value {
+        if let Some(item_schema) = property.get("items") {
+            for item in items {
+                coerce(name, item_schema, item)?;
+            }
+        }
+        return Ok(());
+    }
+    let Value::String(text) = value else {
+        return Ok(());
+    };
+    let text = text.trim();
+    let coerced = if accepts(property, "integer") {
+        text.parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| ArgumentError::new(name, format!("`{text}` is not an integer")))?
+    } else if accepts(property, "number") {
+        text.parse::<f64>()
+            .ok()
+            .and_then(|n| serde_json::Number::from_f64(n).map(Value::Number))
+            .ok_or_else(|| ArgumentError::new(name, format!("`{text}` is not a number")))?
+    } else if accepts(property, "boolean") {
+        text.parse::<bool>()
+            .map(Value::Bool)
+            .map_err(|_| ArgumentError::new(name, format!("`{text}` is not a boolean")))?
+    } else {
+        return Ok(());
+    };
+    *value = coerced;
+    Ok(())
+}
+
+fn check_constraints(name: &str, property: &Value, value: &Value) -> Result<(), ArgumentError> {
+    if let Value::Array(items) = value
+        && let Some(item_schema) = property.get("items")
+    {
+        for item in items {
+            check_constraints(name, item_schema, item)?;
+        }
+        return Ok(());
+    }
+    if let Some(allowed) = property["enum"].as_array()
+        && !allowed.contains(value)
+    {
+        return Err(ArgumentError::new(
+            name,
+            format!("{value} is not one of {}", Value::Array(allowed.clone())),
+        ));
+    }
+    let Some(n) = value.as_f64() else {
+        return Ok(());
+    };
+    if let Some(minimum) = property["minimum"].as_f64()
+        && n < minimum
+    {
+        return Err(ArgumentError::new(
+            name,
+            format!("{n} is below the minimum {minimum}"),
+        ));
+    }
+    if let Some(maximum) = property["maximum"].as_f64()
+        && n > maximum
+    {
+        return Err(ArgumentError::new(
+            name,
+            format!("{n} is above the maximum {maximum}"),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,345 @@
+//! Structured errors returned from `run`. Each error is reported to the
+//! platform as a JSON object with a stable `code`, a human `message` and a
+//! `context` object, e.g.
+//!
+//! ```json
+//! {"code": "invalid_argument", "message": "invalid argument `period`: missing required argument", "context": {"argument": "period"}}
+//! ```
+
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::arguments::ArgumentError;
+use crate::validation::{DataValidationError, SeriesProblem};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FunctionError {
+    InvalidArgument {
+        argument: Option<String>,
+        message: String,
+    },
+    MissingData {
+        problems: Vec<SeriesProblem>,
+    },
+    InsufficientHistory {
+        label: String,
+        required: usize,
+        available: usize,
+    },
+    NumericOverflow {
+        operation: String,
+    },
+    HostCallFailed {
+        call: String,
+        message: String,
+    },
+    State {
+        key: String,
+        message: String,
+    },
+    Internal {
+        message: String,
+    },
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    code: &'a str,
+    message: String,
+    context: Value,
+}
+
+impl FunctionError {
+    pub fn insufficient_history(label: &str, required: usize, available: usize) -> Self {
+        FunctionError::InsufficientHistory {
+            label: label.to_string(),
+            required,
+            available,
+        }
+    }
+
+    pub fn numeric_overflow(operation: impl Into<String>) -> Self {
+        FunctionError::NumericOverflow {
+            operation: operation.into(),
+        }
+    }
+
+    pub fn host_call_failed(call: &str, message: impl fmt::Display) -> Self {
+        FunctionError::HostCallFailed {
+            call: call.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn state(key: &str, message: impl fmt::Display) -> Self {
+        FunctionError::State {
+            key: key.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn internal(message: impl fmt::Display) -> Self {
+        FunctionError::Internal {
+            message: message.to_string(),
+        }
+    }
+
+    /// Stable machine-readable code.
+    pub fn code(&self) -> &'static str {
+        match self {
+            FunctionError::InvalidArgument { .. } => "invalid_argument",
+            FunctionError::MissingData { .. } => "missing_data",
+            FunctionError::InsufficientHistory { .. } => "insufficient_history",
+            FunctionError::NumericOverflow { .. } => "numeric_overflow",
+            FunctionError::HostCallFailed { .. } => "host_call_failed",
+            FunctionError::State { .. } => "state_error",
+            FunctionError::Internal { .. } => "internal",
+        }
+    }
+
+    pub fn context(&self) -> Value {
+        match self {
+            FunctionError::InvalidArgument { argument, .. } => json!({ "argument": argument }),
+            FunctionError::MissingData { problems } => json!({ "problems": problems }),
+            FunctionError::InsufficientHistory {
+                label,
+                required,
+                available,
+            } => json!({ "label": label, "required": required, "available": available }),
+            FunctionError::NumericOverflow { operation } => json!({ "operation": operation }),
+            FunctionError::HostCallFailed { call, message } => {
+                json!({ "call": call, "host_message": message })
+            }
+            FunctionError::State { key, .. } => json!({ "key": key }),
+            FunctionError::Internal { .. } => json!({}),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&Report {
+            code: self.code(),
+            message: self.to_string(),
+            context: self.context(),
+        })
+        .expect("error report serializes to json")
+    }
+}
+
+impl fmt::Display for FunctionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FunctionError::InvalidArgument {
+                argument: Some(argument),
+                message,
+            } => write!(f, "invalid argument `{argument}`: {message}"),
+            FunctionError::InvalidArgument {
+                argument: None,
+                message,
+            } => write!(f, "invalid arguments: {message}"),
+            FunctionError::MissingData { problems } => {
+                write!(
+                    f,
+                    "{}",
+                    DataValidationError {
+                        problems: problems.clone(),
+                    }
+                )
+            }
+            FunctionError::InsufficientHistory {
+                label,
+                required,
+                available,
+            } => write!(
+                f,
+                "`{label}` has {available} candles, at least {required} are required"
+            ),
+            FunctionError::NumericOverflow { operation } => {
+                write!(f, "numeric overflow in {operation}")
+            }
+            FunctionError::HostCallFailed { call, message } => {
+                write!(f, "host call `{call}` failed: {message}")
+            }
+            FunctionError::State { key, message } => write!(f, "state `{key}`: {message}"),
+            FunctionError::Internal { message } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl From<ArgumentError> for FunctionError {
+    fn from(err: ArgumentError) -> Self {
+        FunctionError::InvalidArgument {
+            argument: err.argument,
+            message: err.message,
+        }
+    }
+}
+
+impl From<DataValidationError> for FunctionError {
+    fn from(err: DataValidationError) -> Self {
+        FunctionError::MissingData {
+            problems: err.problems,
+        }
+    }
+}
+
+impl From<extism_pdk::Error> for FunctionError {
+    fn from(err: extism_pdk::Error) -> Self {
+        FunctionError::internal(err)
+    }
+}
+
+/// Lets `?` return a `FunctionError` from a `FnResult`, carrying the JSON report as the message.
+impl From<FunctionError> for extism_pdk::Error {
+    fn from(err: FunctionError) -> Self {
+        extism_pdk::Error::msg(err.to_json())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use extism_pdk::FnResult;
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::validation::SeriesIssue;
+
+    fn report(err: &FunctionError) -> Value {
+        serde_json::from_str(&err.to_json()).unwrap()
+    }
+
+    #[test]
+    fn invalid_argument() {
+        let err = FunctionError::from(ArgumentError {
+            argument: Some("period".to_string()),
+            message: "missing required argument".to_string(),
+        });
+        assert_eq!(
+            report(&err),
+            json!({
+                "code": "invalid_argument",
+                "message": "invalid argument `period`: missing required argument",
+                "context": { "argument": "period" },
+            })
+        );
+
+        let err = FunctionError::from(ArgumentError {
+            argument: None,
+            message: "call arguments must be an object".to_string(),
+        });
+        assert_eq!(
+            report(&err),
+            json!({
+                "code": "invalid_argument",
+                "message": "invalid arguments: call arguments must be an object",
+                "context": { "argument": null },
+            })
+        );
+    }
+
+    #[test]
+    fn missing_data() {
+        let err = FunctionError::from(DataValidationError {
+            problems: vec![
+                SeriesProblem {
+                    label: "btc".to_string(),
+                    issue: SeriesIssue::Missing,
+                },
+                SeriesProblem {
+                    label: "eth".to_string(),
+                    issue: SeriesIssue::Malformed {
+                        index: 3,
+                        reason: "negative volume".to_string(),
+                    },
+                },
+            ],
+        });
+        assert_eq!(
+            report(&err),
+            json!({
+                "code": "missing_data",
+                "message": "invalid financial data: `btc` is missing; `eth` candle 3 is malformed: negative volume;",
+                "context": {
+                    "problems": [
+                        { "label": "btc", "issue": "missing" },
+                        { "label": "eth", "issue": "malformed", "index": 3, "reason": "negative volume" },
+                    ],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn insufficient_history() {
+        assert_eq!(
+            report(&FunctionError::insufficient_history("btc", 200, 50)),
+            json!({
+                "code": "insufficient_history",
+                "message": "`btc` has 50 candles, at least 200 are required",
+                "context": { "label": "btc", "required": 200, "available": 50 },
+            })
+        );
+    }
+
+    #[test]
+    fn numeric_overflow_wire_format() {
+        assert_eq!(
+            FunctionError::numeric_overflow("sma").to_json(),
+            r#"{"code":"numeric_overflow","message":"numeric overflow in sma","context":{"operation":"sma"}}"#
+        );
+    }
+
+    #[test]
+    fn host_call_failed() {
+        assert_eq!(
+            report(&FunctionError::host_call_failed(
+                "schedule_email",
+                "quota exceeded"
+            )),
+            json!({
+                "code": "host_call_failed",
+                "message": "host call `schedule_email` failed: quota exceeded",
+                "context": { "call": "schedule_email", "host_message": "quota exceeded" },
+            })
+        );
+    }
+
+    #[test]
+    fn state() {
+        assert_eq!(
+            report(&FunctionError::state("alerts", "unknown version 3")),
+            json!({
+                "code": "state_error",
+                "message": "state `alerts`: unknown version 3",
+                "context": { "key": "alerts" },
+            })
+        );
+    }
+
+    #[test]
+    fn internal() {
+        assert_eq!(
+            FunctionError::internal("boom").to_json(),
+            r#"{"code":"internal","message":"boom","context":{}}"#
+        );
+    }
+
+    #[test]
+    fn surfaces_report_through_fn_result() {
+        fn plugin() -> FnResult<()> {
+            Err(FunctionError::numeric_overflow("ema"))?;
+            Ok(())
+        }
+
+        let err = plugin().unwrap_err();
+        assert_eq!(
+            err.0.to_string(),
+            FunctionError::numeric_overflow("ema").to_json()
+        );
+        assert_eq!(
+            extism_pdk::Error::from(FunctionError::internal("boom")).to_string(),
+            r#"{"code":"internal","message":"boom","context":{}}"#
+        );
+    }
+}
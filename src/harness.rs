@@ -0,0 +1,245 @@
+//! Native harness for exercising `run` with fixture data, without building
+//! the wasm module or going through the Extism host.
+//!
+//! ```ignore
+//! let output = Fixture::new()
+//!     .with_candles_csv_file("symbol_data", "tests/fixtures/candles.csv")?
+//!     .with_call_argument("period", 20)
+//!     .run()?;
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use exchange_outpost_abi::FunctionArgs;
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+
+use crate::error::FunctionError;
+use crate::host::{ScheduledEffect, mock};
+use crate::{Output, run};
+
+#[derive(Debug)]
+pub enum HarnessError {
+    Io(String),
+    Fixture(String),
+    Run(String),
+}
+
+impl fmt::Display for HarnessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HarnessError::Io(msg) => write!(f, "fixture io error: {msg}"),
+            HarnessError::Fixture(msg) => write!(f, "invalid fixture: {msg}"),
+            HarnessError::Run(msg) => write!(f, "run failed: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for HarnessError {}
+
+/// Builder for the `FunctionArgs` payload the platform would send to `run`.
+#[derive(Debug, Clone, Default)]
+pub struct Fixture {
+    tickers_data: Map<String, Value>,
+    piped_data: Map<String, Value>,
+    call_arguments: Map<String, Value>,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a complete `FunctionArgs` JSON document, e.g. one captured from the platform.
+    pub fn from_json(json: &str) -> Result<Self, HarnessError> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| HarnessError::Fixture(e.to_string()))?;
+        let section = |key: &str| match value.get(key) {
+            Some(Value::Object(map)) => Ok(map.clone()),
+            None | Some(Value::Null) => Ok(Map::new()),
+            Some(_) => Err(HarnessError::Fixture(format!("`{key}` must be an object"))),
+        };
+        Ok(Self {
+            tickers_data: section("tickers_data")?,
+            piped_data: section("piped_data")?,
+            call_arguments: section("call_arguments")?,
+        })
+    }
+
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Self, HarnessError> {
+        Self::from_json(&read_file(path.as_ref())?)
+    }
+
+    /// Adds a candle series under `label`. Each candle is an object with
+    /// `timestamp`, `open`, `high`, `low`, `close` and `volume`.
+    pub fn with_candles(mut self, label: &str, candles: Vec<Value>) -> Self {
+        self.tickers_data.insert(
+            label.to_string(),
+            json!({
+                "symbol": label,
+                "exchange": "fixture",
+                "candles": candles,
+                "precision": 8,
+            }),
+        );
+        self
+    }
+
+    /// Adds a candle series parsed from CSV with a
+    /// `timestamp,open,high,low,close,volume` header.
+    pub fn with_candles_csv(self, label: &str, csv: &str) -> Result<Self, HarnessError> {
+        let candles = parse_candles_csv(csv)?;
+        Ok(self.with_candles(label, candles))
+    }
+
+    pub fn with_candles_csv_file(
+        self,
+        label: &str,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, HarnessError> {
+        let csv = read_file(path.as_ref())?;
+        self.with_candles_csv(label, &csv)
+    }
+
+    pub fn with_call_argument(mut self, key: &str, value: impl Serialize) -> Self {
+        let value = serde_json::to_value(value).unwrap_or(Value::Null);
+        self.call_arguments.insert(key.to_string(), value);
+        self
+    }
+
+    pub fn with_piped_data(mut self, source: &str, value: impl Serialize) -> Self {
+        let value = serde_json::to_value(value).unwrap_or(Value::Null);
+        self.piped_data.insert(source.to_string(), value);
+        self
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "tickers_data": self.tickers_data,
+            "piped_data": self.piped_data,
+            "call_arguments": self.call_arguments,
+        })
+    }
+
+    pub fn build(&self) -> Result<FunctionArgs, HarnessError> {
+        serde_json::from_value(self.to_json()).map_err(|e| HarnessError::Fixture(e.to_string()))
+    }
+
+    /// Builds the `FunctionArgs` and calls `run` natively.
+    pub fn run(&self) -> Result<Output, HarnessError> {
+        Ok(self.run_recorded()?.output)
+    }
+
+    /// Like [`Fixture::run`], also returning the webhooks and emails the run scheduled.
+    pub fn run_recorded(&self) -> Result<RunResult, HarnessError> {
+        self.record(|args| run(args).map_err(|e| e.0.to_string()))
+    }
+
+    /// Like [`Fixture::run_recorded`], calling `f` instead of `run`. Useful to
+    /// test code that schedules effects, such as an `AlertEngine`, before it
+    /// is wired into `run`.
+    pub fn run_recorded_with<O>(
+        &self,
+        f: impl FnOnce(&FunctionArgs) -> Result<O, FunctionError>,
+    ) -> Result<RunResult<O>, HarnessError> {
+        self.record(|args| f(&args).map_err(|e| e.to_json()))
+    }
+
+    fn record<O>(
+        &self,
+        f: impl FnOnce(FunctionArgs) -> Result<O, String>,
+    ) -> Result<RunResult<O>, HarnessError> {
+        let args = self.build()?;
+        mock::clear();
+        let output = f(args).map_err(HarnessError::Run);
+        let effects = mock::take();
+        Ok(RunResult {
+            output: output?,
+            effects,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct RunResult<O = Output> {
+    pub output: O,
+    /// Side effects in the order they were scheduled.
+    pub effects: Vec<ScheduledEffect>,
+}
+
+impl<O> RunResult<O> {
+    /// `(path, payload)` of every scheduled webhook.
+    pub fn webhooks(&self) -> Vec<(&str, &str)> {
+        self.effects
+            .iter()
+            .filter_map(|effect| match effect {
+                ScheduledEffect::Webhook { path, payload } => {
+                    Some((path.as_str(), payload.as_str()))
+                }
+                ScheduledEffect::Email { .. } => None,
+            })
+            .collect()
+    }
+
+    /// `(to, body)` of every scheduled email.
+    pub fn emails(&self) -> Vec<(&str, &str)> {
+        self.effects
+            .iter()
+            .filter_map(|effect| match effect {
+                ScheduledEffect::Email { to, body } => Some((to.as_str(), body.as_str())),
+                ScheduledEffect::Webhook { .. } => None,
+            })
+            .collect()
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, HarnessError> {
+    fs::read_to_string(path).map_err(|e| HarnessError::Io(format!("{}: {e}", path.display())))
+}
+
+fn parse_candles_csv(csv: &str) -> Result<Vec<Value>, HarnessError> {
+    const COLUMNS: [&str; 6] = ["timestamp", "open", "high", "low", "close", "volume"];
+
+    let mut lines = csv.lines().map(str::trim).filter(|l| !l.is_empty());
+    let header: Vec<&str> = lines
+        .next()
+        .ok_or_else(|| HarnessError::Fixture("empty csv".to_string()))?
+        .split(',')
+        .map(str::trim)
+        .collect();
+    let indexes = COLUMNS
+        .iter()
+        .map(|column| {
+            header
+                .iter()
+                .position(|h| h.eq_ignore_ascii_case(column))
+                .ok_or_else(|| HarnessError::Fixture(format!("missing csv column `{column}`")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    lines
+        .enumerate()
+        .map(|(row, line)| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let field = |i: usize| {
+                fields.get(indexes[i]).copied().ok_or_else(|| {
+                    HarnessError::Fixture(format!("row {}: missing `{}`", row + 1, COLUMNS[i]))
+                })
+            };
+            let timestamp: i64 = field(0)?
+                .parse()
+                .map_err(|e| HarnessError::Fixture(format!("row {}: timestamp: {e}", row + 1)))?;
+            let mut candle = Map::new();
+            candle.insert("timestamp".to_string(), json!(timestamp));
+            for (i, column) in COLUMNS.iter().enumerate().skip(1) {
+                let value: f64 = field(i)?.parse().map_err(|e| {
+                    HarnessError::Fixture(format!("row {}: {column}: {e}", row + 1))
+                })?;
+                candle.insert(column.to_string(), json!(value));
+            }
+            Ok(Value::Object(candle))
+        })
+        .collect()
+}
@@ -0,0 +1,137 @@
+//! Host-backed side effects and storage. On wasm32 these forward to the
+//! ExchangeOutpost host; natively they are captured by an in-process mock so
+//! tests can assert on what a run would have scheduled or stored.
+
+use crate::error::FunctionError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduledEffect {
+    Webhook { path: String, payload: String },
+    Email { to: String, body: String },
+}
+
+/// Schedules a POST of `payload` to `path`, relative to the organization's webhook base url.
+pub fn schedule_webhook(path: &str, payload: &str) -> Result<(), FunctionError> {
+    #[cfg(target_arch = "wasm32")]
+    exchange_outpost_abi::schedule_webhook(path, payload)
+        .map_err(|e| FunctionError::host_call_failed("schedule_webhook", e))?;
+    #[cfg(not(target_arch = "wasm32"))]
+    mock::record(ScheduledEffect::Webhook {
+        path: path.to_string(),
+        payload: payload.to_string(),
+    });
+    Ok(())
+}
+
+/// Schedules an email with `body` to `to`.
+pub fn schedule_email(to: &str, body: &str) -> Result<(), FunctionError> {
+    #[cfg(target_arch = "wasm32")]
+    exchange_outpost_abi::schedule_email(to, body)
+        .map_err(|e| FunctionError::host_call_failed("schedule_email", e))?;
+    #[cfg(not(target_arch = "wasm32"))]
+    mock::record(ScheduledEffect::Email {
+        to: to.to_string(),
+        body: body.to_string(),
+    });
+    Ok(())
+}
+
+/// Reads a persisted variable.
+pub fn var_get(key: &str) -> Result<Option<Vec<u8>>, FunctionError> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        extism_pdk::var::get::<Vec<u8>>(key)
+            .map_err(|e| FunctionError::host_call_failed("var_get", e))
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Ok(mock::var(key))
+    }
+}
+
+/// Persists a variable across invocations of the function.
+pub fn var_set(key: &str, value: Vec<u8>) -> Result<(), FunctionError> {
+    #[cfg(target_arch = "wasm32")]
+    extism_pdk::var::set(key, value).map_err(|e| FunctionError::host_call_failed("var_set", e))?;
+    #[cfg(not(target_arch = "wasm32"))]
+    mock::set_var(key, value);
+    Ok(())
+}
+
+pub fn var_remove(key: &str) -> Result<(), FunctionError> {
+    #[cfg(target_arch = "wasm32")]
+    extism_pdk::var::remove(key).map_err(|e| FunctionError::host_call_failed("var_remove", e))?;
+    #[cfg(not(target_arch = "wasm32"))]
+    mock::remove_var(key);
+    Ok(())
+}
+
+/// Reads a value from the plugin's Extism config, e.g. a secret set by the host.
+pub fn config_get(key: &str) -> Result<Option<String>, FunctionError> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        extism_pdk::config::get(key).map_err(|e| FunctionError::host_call_failed("config_get", e))
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Ok(mock::config(key))
+    }
+}
+
+/// Per-thread recorder standing in for the host in native runs.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod mock {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    use super::ScheduledEffect;
+
+    thread_local! {
+        static EFFECTS: RefCell<Vec<ScheduledEffect>> = const { RefCell::new(Vec::new()) };
+        static VARS: RefCell<HashMap<String, Vec<u8>>> = RefCell::new(HashMap::new());
+        static CONFIG: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+    }
+
+    pub(crate) fn record(effect: ScheduledEffect) {
+        EFFECTS.with(|effects| effects.borrow_mut().push(effect));
+    }
+
+    /// Returns the effects scheduled on this thread, in order, and clears them.
+    pub fn take() -> Vec<ScheduledEffect> {
+        EFFECTS.with(|effects| effects.take())
+    }
+
+    pub fn clear() {
+        EFFECTS.with(|effects| effects.borrow_mut().clear());
+    }
+
+    /// Current value of a variable. Variables persist across runs on the
+    /// same thread, like they do across invocations on the host.
+    pub fn var(key: &str) -> Option<Vec<u8>> {
+        VARS.with(|vars| vars.borrow().get(key).cloned())
+    }
+
+    pub fn set_var(key: &str, value: Vec<u8>) {
+        VARS.with(|vars| vars.borrow_mut().insert(key.to_string(), value));
+    }
+
+    pub fn remove_var(key: &str) {
+        VARS.with(|vars| vars.borrow_mut().remove(key));
+    }
+
+    pub fn clear_vars() {
+        VARS.with(|vars| vars.borrow_mut().clear());
+    }
+
+    pub fn config(key: &str) -> Option<String> {
+        CONFIG.with(|config| config.borrow().get(key).cloned())
+    }
+
+    pub fn set_config(key: &str, value: &str) {
+        CONFIG.with(|config| {
+            config
+                .borrow_mut()
+                .insert(key.to_string(), value.to_string())
+        });
+    }
+}
@@ -0,0 +1,384 @@
+//! Ichimoku Kinko Hyo. The Senkou spans are projected `displacement` bars
+//! past the last candle, on timestamps synthesized from the inferred candle
+//! interval.
+
+use exchange_outpost_abi::Candle;
+use serde::{Deserialize, Serialize};
+
+use super::trend::Direction;
+use super::{Number, Source, highest, infer_interval, lowest};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IchimokuSettings {
+    pub tenkan: usize,
+    pub kijun: usize,
+    pub senkou_b: usize,
+    pub displacement: usize,
+}
+
+impl Default for IchimokuSettings {
+    fn default() -> Self {
+        Self {
+            tenkan: 9,
+            kijun: 26,
+            senkou_b: 52,
+            displacement: 26,
+        }
+    }
+}
+
+/// Ichimoku lines as plotted at one bar.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct IchimokuBar<T> {
+    pub timestamp: i64,
+    pub tenkan: Option<T>,
+    pub kijun: Option<T>,
+    /// Computed `displacement` bars earlier.
+    pub senkou_a: Option<T>,
+    /// Computed `displacement` bars earlier.
+    pub senkou_b: Option<T>,
+    /// Close from `displacement` bars later.
+    pub chikou: Option<T>,
+}
+
+impl<T: Number> IchimokuBar<T> {
+    /// Top and bottom of the cloud at this bar.
+    pub fn cloud(&self) -> Option<(T, T)> {
+        let (a, b) = (self.senkou_a?, self.senkou_b?);
+        Some((a.max(b), a.min(b)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalKind {
+    /// Tenkan crossed Kijun.
+    TkCross,
+    /// Close moved from inside or below the cloud to above it, or the reverse.
+    CloudCross,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct IchimokuSignal {
+    pub kind: SignalKind,
+    pub index: usize,
+    pub timestamp: i64,
+    pub direction: Direction,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Ichimoku<T> {
+    /// One bar per candle followed by `displacement` projected bars. The
+    /// projection is omitted when the candle interval can't be inferred.
+    pub bars: Vec<IchimokuBar<T>>,
+    pub signals: Vec<IchimokuSignal>,
+}
+
+fn midpoint<T: Number>(highs: &[T], lows: &[T], period: usize) -> Vec<Option<T>> {
+    let two = T::from_usize(2);
+    highest(highs, period)
+        .into_iter()
+        .zip(lowest(lows, period))
+        .map(|(h, l)| Some((h? + l?) / two))
+        .collect()
+}
+
+pub fn ichimoku<T: Number>(candles: &[Candle<T>], settings: IchimokuSettings) -> Ichimoku<T> {
+    let highs = Source::High.values(candles);
+    let lows = Source::Low.values(candles);
+    let tenkan = midpoint(&highs, &lows, settings.tenkan);
+    let kijun = midpoint(&highs, &lows, settings.kijun);
+    let span_b = midpoint(&highs, &lows, settings.senkou_b);
+    let two = T::from_usize(2);
+    let span_a: Vec<Option<T>> = tenkan
+        .iter()
+        .zip(&kijun)
+        .map(|(t, k)| Some(((*t)? + (*k)?) / two))
+        .collect();
+
+    let len = candles.len();
+    let shift = settings.displacement;
+    let future = match (infer_interval(candles), candles.last()) {
+        (Some(interval), Some(last)) => (1..=shift as i64)
+            .map(|n| last.timestamp + n * interval)
+            .collect(),
+        _ => Vec::new(),
+    };
+    let timestamps = candles.iter().map(|c| c.timestamp).chain(future);
+
+    let bars: Vec<IchimokuBar<T>> = timestamps
+        .enumerate()
+        .map(|(i, timestamp)| {
+            let source = i.checked_sub(shift);
+            IchimokuBar {
+                timestamp,
+                tenkan: tenkan.get(i).copied().flatten(),
+                kijun: kijun.get(i).copied().flatten(),
+                senkou_a: source.and_then(|s| span_a[s]),
+                senkou_b: source.and_then(|s| span_b[s]),
+                chikou: candles.get(i + shift).filter(|_| i < len).map(|c| c.close),
+            }
+        })
+        .collect();
+
+    let signals = signals(candles, &bars[..len]);
+    Ichimoku { bars, signals }
+}
+
+fn signals<T: Number>(candles: &[Candle<T>], bars: &[IchimokuBar<T>]) -> Vec<IchimokuSignal> {
+    let mut signals = Vec::new();
+    for i in 1..bars.len() {
+        let (prev, bar) = (&bars[i - 1], &bars[i]);
+        let mut push = |kind, direction| {
+            signals.push(IchimokuSignal {
+                kind,
+                index: i,
+                timestamp: bar.timestamp,
+                direction,
+            })
+        };
+
+        if let (Some(pt), Some(pk), Some(t), Some(k)) =
+            (prev.tenkan, prev.kijun, bar.tenkan, bar.kijun)
+        {
+            if pt <= pk && t > k {
+                push(SignalKind::TkCross, Direction::Up);
+            } else if pt >= pk && t < k {
+                push(SignalKind::TkCross, Direction::Down);
+            }
+        }
+
+        if let (Some((prev_top, prev_bottom)), Some((top, bottom))) = (prev.cloud(), bar.cloud()) {
+            let (prev_close, close) = (candles[i - 1].close, candles[i].close);
+            if prev_close <= prev_top && close > top {
+                push(SignalKind::CloudCross, Direction::Up);
+            } else if prev_close >= prev_bottom && close < bottom {
+                push(SignalKind::CloudCross, Direction::Down);
+            }
+        }
+    }
+    signals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{candles_at, decimal_candles};
+
+    const SETTINGS: IchimokuSettings = IchimokuSettings {
+        tenkan: 2,
+        kijun: 3,
+        senkou_b: 4,
+        displacement: 2,
+    };
+
+    /// `(high, low, close)`, falling into bar 3, rallying to bar 7 and
+    /// falling again.
+    const PRICES: [(f64, f64, f64); 12] = [
+        (11.0, 9.0, 10.0),
+        (10.0, 8.0, 9.0),
+        (9.0, 7.0, 8.0),
+        (8.0, 6.0, 7.0),
+        (9.0, 7.0, 8.5),
+        (10.0, 8.0, 8.5),
+        (13.0, 11.0, 12.5),
+        (14.0, 12.0, 13.5),
+        (13.0, 11.0, 11.5),
+        (11.0, 9.0, 9.5),
+        (9.0, 7.0, 7.5),
+        (8.0, 6.0, 6.5),
+    ];
+
+    /// One-minute candles with the bar after 180 missing.
+    const TIMESTAMPS: [i64; 12] = [0, 60, 120, 180, 300, 360, 420, 480, 540, 600, 660, 720];
+
+    fn rows() -> Vec<[f64; 5]> {
+        PRICES
+            .iter()
+            .map(|&(high, low, close)| [close, high, low, close, 1.0])
+            .collect()
+    }
+
+    fn series() -> Vec<Candle<f64>> {
+        let rows: Vec<(i64, [f64; 5])> = TIMESTAMPS.into_iter().zip(rows()).collect();
+        candles_at(&rows)
+    }
+
+    fn column(
+        bars: &[IchimokuBar<f64>],
+        f: fn(&IchimokuBar<f64>) -> Option<f64>,
+    ) -> Vec<Option<f64>> {
+        bars.iter().map(f).collect()
+    }
+
+    #[test]
+    fn projects_displacement_bars_on_the_inferred_interval() {
+        let result = ichimoku(&series(), SETTINGS);
+        assert_eq!(result.bars.len(), 14);
+        let timestamps: Vec<i64> = result.bars.iter().map(|b| b.timestamp).collect();
+        assert_eq!(timestamps[..12], TIMESTAMPS);
+        assert_eq!(timestamps[12..], [780, 840]);
+        assert!(
+            result.bars[12..]
+                .iter()
+                .all(|b| b.tenkan.is_none() && b.kijun.is_none() && b.chikou.is_none())
+        );
+    }
+
+    #[test]
+    fn omits_the_projection_without_an_interval() {
+        let result = ichimoku(&series()[..1], SETTINGS);
+        assert_eq!(result.bars.len(), 1);
+        assert!(result.signals.is_empty());
+        assert!(ichimoku::<f64>(&[], SETTINGS).bars.is_empty());
+    }
+
+    #[test]
+    fn conversion_and_base_lines() {
+        let bars = ichimoku(&series(), SETTINGS).bars;
+        assert_eq!(
+            column(&bars, |b| b.tenkan),
+            [
+                None,
+                Some(9.5),
+                Some(8.5),
+                Some(7.5),
+                Some(7.5),
+                Some(8.5),
+                Some(10.5),
+                Some(12.5),
+                Some(12.5),
+                Some(11.0),
+                Some(9.0),
+                Some(7.5),
+                None,
+                None
+            ]
+        );
+        assert_eq!(
+            column(&bars, |b| b.kijun),
+            [
+                None,
+                None,
+                Some(9.0),
+                Some(8.0),
+                Some(7.5),
+                Some(8.0),
+                Some(10.0),
+                Some(11.0),
+                Some(12.5),
+                Some(11.5),
+                Some(10.0),
+                Some(8.5),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn senkou_spans_are_shifted_forward() {
+        let bars = ichimoku(&series(), SETTINGS).bars;
+        assert_eq!(
+            column(&bars, |b| b.senkou_a),
+            [
+                None,
+                None,
+                None,
+                None,
+                Some(8.75),
+                Some(7.75),
+                Some(7.5),
+                Some(8.25),
+                Some(10.25),
+                Some(11.75),
+                Some(12.5),
+                Some(11.25),
+                Some(9.5),
+                Some(8.0)
+            ]
+        );
+        assert_eq!(
+            column(&bars, |b| b.senkou_b),
+            [
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(8.5),
+                Some(8.0),
+                Some(8.0),
+                Some(9.5),
+                Some(10.5),
+                Some(11.0),
+                Some(11.5),
+                Some(10.5),
+                Some(9.5)
+            ]
+        );
+        assert_eq!(bars[11].cloud(), Some((11.5, 11.25)));
+        assert_eq!(bars[4].cloud(), None);
+    }
+
+    #[test]
+    fn chikou_is_the_close_displacement_bars_later() {
+        let bars = ichimoku(&series(), SETTINGS).bars;
+        assert_eq!(
+            column(&bars, |b| b.chikou),
+            [
+                Some(8.0),
+                Some(7.0),
+                Some(8.5),
+                Some(8.5),
+                Some(12.5),
+                Some(13.5),
+                Some(11.5),
+                Some(9.5),
+                Some(7.5),
+                Some(6.5),
+                None,
+                None,
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn tk_and_cloud_crosses() {
+        let signal = |kind, index: usize, direction| IchimokuSignal {
+            kind,
+            index,
+            timestamp: TIMESTAMPS[index],
+            direction,
+        };
+        assert_eq!(
+            ichimoku(&series(), SETTINGS).signals,
+            vec![
+                signal(SignalKind::TkCross, 5, Direction::Up),
+                signal(SignalKind::CloudCross, 6, Direction::Up),
+                signal(SignalKind::TkCross, 9, Direction::Down),
+                signal(SignalKind::CloudCross, 9, Direction::Down),
+            ]
+        );
+    }
+
+    #[test]
+    fn decimal_matches_f64() {
+        let result = ichimoku(&decimal_candles(&rows()), SETTINGS);
+        let f64_result = ichimoku(&series(), SETTINGS);
+        assert_eq!(result.bars.len(), f64_result.bars.len());
+        for (decimal, float) in result.bars.iter().zip(&f64_result.bars) {
+            assert_eq!(decimal.senkou_a.map(Number::to_f64), float.senkou_a);
+            assert_eq!(decimal.senkou_b.map(Number::to_f64), float.senkou_b);
+        }
+        let kinds = |signals: &[IchimokuSignal]| -> Vec<(SignalKind, usize, Direction)> {
+            signals
+                .iter()
+                .map(|s| (s.kind, s.index, s.direction))
+                .collect()
+        };
+        assert_eq!(kinds(&result.signals), kinds(&f64_result.signals));
+    }
+}
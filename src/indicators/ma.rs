@@ -0,0 +1,340 @@
+//! Moving averages. The slice functions take raw values; [`moving_average`]
+//! reads them from candles through a [`Source`].
+
+use exchange_outpost_abi::Candle;
+use serde::{Deserialize, Serialize};
+
+use super::{Number, Source, on_defined, zip_with};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MaKind {
+    Sma,
+    Ema,
+    Wma,
+    Dema,
+    Tema,
+    Hma,
+    Kama,
+}
+
+impl MaKind {
+    /// Number of leading `None`s for a series of the given period. `None`
+    /// for a period of 0, which never produces a value.
+    pub fn warm_up(self, period: usize) -> Option<usize> {
+        let p = period.checked_sub(1)?;
+        Some(match self {
+            MaKind::Sma | MaKind::Ema | MaKind::Wma | MaKind::Kama => p,
+            MaKind::Dema => 2 * p,
+            MaKind::Tema => 3 * p,
+            MaKind::Hma => p + hma_sqrt_period(period) - 1,
+        })
+    }
+
+    pub fn compute<T: Number>(self, values: &[T], period: usize) -> Vec<Option<T>> {
+        match self {
+            MaKind::Sma => sma(values, period),
+            MaKind::Ema => ema(values, period),
+            MaKind::Wma => wma(values, period),
+            MaKind::Dema => dema(values, period),
+            MaKind::Tema => tema(values, period),
+            MaKind::Hma => hma(values, period),
+            MaKind::Kama => kama(values, period, 2, 30),
+        }
+    }
+}
+
+/// Moving average of `source` over `candles`, aligned with the candles.
+pub fn moving_average<T: Number>(
+    candles: &[Candle<T>],
+    kind: MaKind,
+    period: usize,
+    source: Source,
+) -> Vec<Option<T>> {
+    kind.compute(&source.values(candles), period)
+}
+
+pub fn sma<T: Number>(values: &[T], period: usize) -> Vec<Option<T>> {
+    let mut out = vec![None; values.len()];
+    if period == 0 || values.len() < period {
+        return out;
+    }
+    let n = T::from_usize(period);
+    let mut sum = values[..period].iter().fold(T::zero(), |acc, v| acc + *v);
+    out[period - 1] = Some(sum / n);
+    for i in period..values.len() {
+        sum = sum + values[i] - values[i - period];
+        out[i] = Some(sum / n);
+    }
+    out
+}
+
+/// Exponential moving average with `alpha = 2 / (period + 1)`, seeded with
+/// the SMA of the first `period` values.
+pub fn ema<T: Number>(values: &[T], period: usize) -> Vec<Option<T>> {
+    let alpha = T::from_usize(2) / T::from_usize(period + 1);
+    smoothed(values, period, alpha)
+}
+
+/// Wilder's smoothing (RMA), an EMA with `alpha = 1 / period`.
+pub fn rma<T: Number>(values: &[T], period: usize) -> Vec<Option<T>> {
+    let alpha = T::one() / T::from_usize(period.max(1));
+    smoothed(values, period, alpha)
+}
+
+fn smoothed<T: Number>(values: &[T], period: usize, alpha: T) -> Vec<Option<T>> {
+    let mut out = vec![None; values.len()];
+    if period == 0 || values.len() < period {
+        return out;
+    }
+    let mut prev =
+        values[..period].iter().fold(T::zero(), |acc, v| acc + *v) / T::from_usize(period);
+    out[period - 1] = Some(prev);
+    for i in period..values.len() {
+        prev = prev + alpha * (values[i] - prev);
+        out[i] = Some(prev);
+    }
+    out
+}
+
+/// Linearly weighted moving average, the newest value weighted `period`.
+pub fn wma<T: Number>(values: &[T], period: usize) -> Vec<Option<T>> {
+    let mut out = vec![None; values.len()];
+    if period == 0 || values.len() < period {
+        return out;
+    }
+    let denominator = T::from_usize(period * (period + 1) / 2);
+    for i in period - 1..values.len() {
+        let window = &values[i + 1 - period..=i];
+        let weighted = window
+            .iter()
+            .enumerate()
+            .fold(T::zero(), |acc, (w, v)| acc + T::from_usize(w + 1) * *v);
+        out[i] = Some(weighted / denominator);
+    }
+    out
+}
+
+/// Double EMA: `2 * EMA - EMA(EMA)`.
+pub fn dema<T: Number>(values: &[T], period: usize) -> Vec<Option<T>> {
+    let e1 = ema(values, period);
+    let e2 = on_defined(&e1, |v| ema(v, period));
+    zip_with(&e1, &e2, |a, b| T::from_usize(2) * a - b)
+}
+
+/// Triple EMA: `3 * EMA - 3 * EMA(EMA) + EMA(EMA(EMA))`.
+pub fn tema<T: Number>(values: &[T], period: usize) -> Vec<Option<T>> {
+    let e1 = ema(values, period);
+    let e2 = on_defined(&e1, |v| ema(v, period));
+    let e3 = on_defined(&e2, |v| ema(v, period));
+    let three = T::from_usize(3);
+    let partial = zip_with(&e1, &e2, |a, b| three * (a - b));
+    zip_with(&partial, &e3, |p, c| p + c)
+}
+
+fn hma_sqrt_period(period: usize) -> usize {
+    ((period as f64).sqrt().floor() as usize).max(1)
+}
+
+/// Hull moving average: `WMA(2 * WMA(n / 2) - WMA(n), sqrt(n))`.
+pub fn hma<T: Number>(values: &[T], period: usize) -> Vec<Option<T>> {
+    if period == 0 {
+        return vec![None; values.len()];
+    }
+    let half = wma(values, (period / 2).max(1));
+    let full = wma(values, period);
+    let diff = zip_with(&half, &full, |h, f| T::from_usize(2) * h - f);
+    on_defined(&diff, |v| wma(v, hma_sqrt_period(period)))
+}
+
+/// Kaufman adaptive moving average over an efficiency ratio of `period`
+/// bars, with smoothing between the `fast` and `slow` EMA constants
+/// (commonly 10, 2, 30). Seeded with the value at `period - 1`.
+pub fn kama<T: Number>(values: &[T], period: usize, fast: usize, slow: usize) -> Vec<Option<T>> {
+    let mut out = vec![None; values.len()];
+    if period == 0 || values.len() < period {
+        return out;
+    }
+    let fast_sc = T::from_usize(2) / T::from_usize(fast + 1);
+    let slow_sc = T::from_usize(2) / T::from_usize(slow + 1);
+    let mut prev = values[period - 1];
+    out[period - 1] = Some(prev);
+    for i in period..values.len() {
+        let change = (values[i] - values[i - period]).abs();
+        let volatility =
+            (i + 1 - period..=i).fold(T::zero(), |acc, j| acc + (values[j] - values[j - 1]).abs());
+        let er = if volatility > T::zero() {
+            change / volatility
+        } else {
+            T::zero()
+        };
+        let sc = er * (fast_sc - slow_sc) + slow_sc;
+        prev = prev + sc * sc * (values[i] - prev);
+        out[i] = Some(prev);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        assert_series, candles, decimals, padded, to_f64, warm_up as leading_nones,
+    };
+
+    /// Closes from StockCharts' moving average worked example.
+    const PRICES: [f64; 30] = [
+        22.27, 22.19, 22.08, 22.17, 22.18, 22.13, 22.23, 22.43, 22.24, 22.29, 22.15, 22.39, 22.38,
+        22.61, 23.36, 24.05, 23.75, 23.83, 23.95, 23.63, 23.82, 23.87, 23.65, 23.19, 23.10, 23.33,
+        22.68, 23.10, 22.40, 22.17,
+    ];
+
+    const KINDS: [MaKind; 7] = [
+        MaKind::Sma,
+        MaKind::Ema,
+        MaKind::Wma,
+        MaKind::Dema,
+        MaKind::Tema,
+        MaKind::Hma,
+        MaKind::Kama,
+    ];
+
+    fn linear(len: usize) -> Vec<f64> {
+        (0..len).map(|i| 100.0 + 2.5 * i as f64).collect()
+    }
+
+    #[test]
+    fn sma_matches_reference() {
+        let expected = padded(
+            9,
+            &[
+                22.221, 22.209, 22.229, 22.259, 22.303, 22.421, 22.613, 22.765, 22.905, 23.076,
+                23.21, 23.377, 23.525, 23.652, 23.71, 23.684, 23.612, 23.505, 23.432, 23.277,
+                23.131,
+            ],
+        );
+        assert_series(&sma(&PRICES, 10), &expected, 1e-9);
+    }
+
+    #[test]
+    fn ema_matches_reference() {
+        // Published values, rounded to cents.
+        let expected = padded(
+            9,
+            &[
+                22.22, 22.21, 22.24, 22.27, 22.33, 22.52, 22.80, 22.97, 23.13, 23.28, 23.34, 23.43,
+                23.51, 23.53, 23.47, 23.40, 23.39, 23.26, 23.23, 23.08, 22.92,
+            ],
+        );
+        assert_series(&ema(&PRICES, 10), &expected, 0.005);
+    }
+
+    #[test]
+    fn rma_uses_wilder_alpha() {
+        // Seed 2, then prev + (value - prev) / 3.
+        let expected = padded(2, &[2.0, 3.0, 4.0 + 2.0 / 3.0]);
+        assert_series(&rma(&[1.0, 2.0, 3.0, 5.0, 8.0], 3), &expected, 1e-12);
+    }
+
+    #[test]
+    fn wma_matches_reference() {
+        assert_series(&wma(&[1.0, 2.0, 3.0], 3), &padded(2, &[14.0 / 6.0]), 1e-12);
+        let tail = &wma(&PRICES, 5)[24..];
+        assert_series(
+            tail,
+            &padded(0, &[23.3847, 23.3193, 23.07, 23.04, 22.8133, 22.5627]),
+            1e-4,
+        );
+    }
+
+    #[test]
+    fn dema_tema_and_hma_remove_linear_lag() {
+        // EMA and WMA lag a linear trend by a constant, which the combined
+        // averages cancel out exactly.
+        let values = linear(40);
+        for (kind, period) in [(MaKind::Dema, 5), (MaKind::Tema, 4), (MaKind::Hma, 9)] {
+            let warm = kind.warm_up(period).unwrap();
+            let expected = padded(warm, &values[warm..]);
+            assert_series(&kind.compute(&values, period), &expected, 1e-9);
+        }
+        // A plain EMA trails by (period - 1) / 2.
+        let expected = padded(4, &values[4..].iter().map(|v| v - 5.0).collect::<Vec<_>>());
+        assert_series(&ema(&values, 5), &expected, 1e-9);
+    }
+
+    #[test]
+    fn kama_matches_reference() {
+        let expected = padded(
+            9,
+            &[
+                22.29, 22.2874, 22.2903, 22.2951, 22.3201, 22.5097, 22.9091, 23.0379, 23.1517,
+                23.3199, 23.3568, 23.4338, 23.5, 23.5158, 23.5059, 23.4993, 23.4865, 23.3977,
+                23.3827, 23.2659, 23.1423,
+            ],
+        );
+        assert_series(&kama(&PRICES, 10, 2, 30), &expected, 1e-4);
+    }
+
+    #[test]
+    fn kama_holds_on_flat_input() {
+        let values = [5.0; 12];
+        assert_series(&kama(&values, 4, 2, 30), &padded(3, &[5.0; 9]), 1e-12);
+    }
+
+    #[test]
+    fn warm_up_matches_leading_nones() {
+        let values = PRICES.repeat(2);
+        let decimal_values = decimals(&values);
+        for kind in KINDS {
+            for period in [1, 2, 3, 4, 5, 9, 10, 16] {
+                let expected = kind.warm_up(period).unwrap();
+                assert_eq!(
+                    leading_nones(&kind.compute(&values, period)),
+                    expected,
+                    "{kind:?}({period}) f64"
+                );
+                assert_eq!(
+                    leading_nones(&kind.compute(&decimal_values, period)),
+                    expected,
+                    "{kind:?}({period}) Decimal"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn period_zero_never_produces_values() {
+        for kind in KINDS {
+            assert_eq!(kind.warm_up(0), None);
+            assert!(kind.compute(&PRICES, 0).iter().all(Option::is_none));
+        }
+    }
+
+    #[test]
+    fn decimal_matches_f64() {
+        let decimal_values = decimals(&PRICES);
+        for kind in KINDS {
+            let expected = kind.compute(&PRICES, 5);
+            assert_series(&to_f64(&kind.compute(&decimal_values, 5)), &expected, 1e-9);
+        }
+    }
+
+    #[test]
+    fn moving_average_reads_source() {
+        let candles = candles(&[
+            [1.0, 4.0, 2.0, 3.0, 1.0],
+            [3.0, 6.0, 2.0, 5.0, 1.0],
+            [5.0, 8.0, 4.0, 7.0, 1.0],
+        ]);
+        assert_series(
+            &moving_average(&candles, MaKind::Sma, 2, Source::Hl2),
+            &padded(1, &[3.5, 5.0]),
+            1e-12,
+        );
+        assert_series(
+            &moving_average(&candles, MaKind::Sma, 3, Source::Close),
+            &padded(2, &[5.0]),
+            1e-12,
+        );
+    }
+}
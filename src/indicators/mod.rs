@@ -0,0 +1,212 @@
+//! Technical indicators over the `Candle` series returned by `get_candles`
+//! and `get_candles_decimal`.
+//!
+//! Every indicator returns one value per input candle, so `result[i]` belongs
+//! to `candles[i]`. Bars inside an indicator's warm-up period are `None`.
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use exchange_outpost_abi::Candle;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::{Decimal, MathematicalOps};
+use serde::{Deserialize, Serialize};
+
+pub mod ichimoku;
+pub mod ma;
+pub mod oscillators;
+pub mod streaming;
+pub mod trend;
+pub mod volatility;
+pub mod volume;
+
+/// Numeric type indicators are computed in: `f64` or `Decimal`.
+pub trait Number:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn zero() -> Self;
+    fn from_usize(n: usize) -> Self;
+    fn from_f64(n: f64) -> Self;
+    fn to_f64(self) -> f64;
+    fn sqrt(self) -> Self;
+
+    fn one() -> Self {
+        Self::from_usize(1)
+    }
+
+    fn abs(self) -> Self {
+        if self < Self::zero() { -self } else { self }
+    }
+
+    fn max(self, other: Self) -> Self {
+        if other > self { other } else { self }
+    }
+
+    fn min(self, other: Self) -> Self {
+        if other < self { other } else { self }
+    }
+}
+
+impl Number for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn from_usize(n: usize) -> Self {
+        n as f64
+    }
+
+    fn from_f64(n: f64) -> Self {
+        n
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+}
+
+impl Number for Decimal {
+    fn zero() -> Self {
+        Decimal::ZERO
+    }
+
+    fn from_usize(n: usize) -> Self {
+        Decimal::from(n)
+    }
+
+    fn from_f64(n: f64) -> Self {
+        <Decimal as FromPrimitive>::from_f64(n).unwrap_or_default()
+    }
+
+    fn to_f64(self) -> f64 {
+        <Decimal as ToPrimitive>::to_f64(&self).unwrap_or_default()
+    }
+
+    fn sqrt(self) -> Self {
+        MathematicalOps::sqrt(&self).unwrap_or_default()
+    }
+}
+
+/// Candle price an indicator is computed from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Open,
+    High,
+    Low,
+    #[default]
+    Close,
+    /// (high + low) / 2
+    Hl2,
+    /// (high + low + close) / 3
+    Hlc3,
+    /// (open + high + low + close) / 4
+    Ohlc4,
+}
+
+impl Source {
+    pub fn value<T: Number>(self, candle: &Candle<T>) -> T {
+        match self {
+            Source::Open => candle.open,
+            Source::High => candle.high,
+            Source::Low => candle.low,
+            Source::Close => candle.close,
+            Source::Hl2 => (candle.high + candle.low) / T::from_usize(2),
+            Source::Hlc3 => (candle.high + candle.low + candle.close) / T::from_usize(3),
+            Source::Ohlc4 => {
+                (candle.open + candle.high + candle.low + candle.close) / T::from_usize(4)
+            }
+        }
+    }
+
+    pub fn values<T: Number>(self, candles: &[Candle<T>]) -> Vec<T> {
+        candles.iter().map(|c| self.value(c)).collect()
+    }
+}
+
+/// Pairs each indicator value with its candle's timestamp.
+pub fn with_timestamps<T, V: Clone>(candles: &[Candle<T>], values: &[V]) -> Vec<(i64, V)> {
+    candles
+        .iter()
+        .zip(values)
+        .map(|(candle, value)| (candle.timestamp, value.clone()))
+        .collect()
+}
+
+/// Candle interval in timestamp units: the most common gap between
+/// consecutive candles, so occasional missing bars don't skew it. `None` for
+/// fewer than two candles.
+pub fn infer_interval<T>(candles: &[Candle<T>]) -> Option<i64> {
+    let mut gaps: Vec<i64> = candles
+        .windows(2)
+        .map(|w| w[1].timestamp - w[0].timestamp)
+        .filter(|gap| *gap > 0)
+        .collect();
+    gaps.sort_unstable();
+    gaps.chunk_by(|a, b| a == b)
+        .max_by_key(|run| (run.len(), std::cmp::Reverse(run[0])))
+        .map(|run| run[0])
+}
+
+/// Applies `f` to the defined tail of `series` (everything after the leading
+/// `None`s) and re-pads the result, so indicators can be chained.
+pub(crate) fn on_defined<T: Copy, U>(
+    series: &[Option<T>],
+    f: impl FnOnce(&[T]) -> Vec<Option<U>>,
+) -> Vec<Option<U>> {
+    let start = series
+        .iter()
+        .position(Option::is_some)
+        .unwrap_or(series.len());
+    let tail: Vec<T> = series[start..].iter().map_while(|v| *v).collect();
+    let mut out: Vec<Option<U>> = (0..start).map(|_| None).collect();
+    out.extend(f(&tail));
+    out.resize_with(series.len(), || None);
+    out
+}
+
+/// Element-wise combination of two aligned series; `None` where either is `None`.
+pub(crate) fn zip_with<T: Copy, U>(
+    a: &[Option<T>],
+    b: &[Option<T>],
+    f: impl Fn(T, T) -> U,
+) -> Vec<Option<U>> {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| Some(f((*a)?, (*b)?)))
+        .collect()
+}
+
+/// Highest value of each trailing `period` window.
+pub fn highest<T: Number>(values: &[T], period: usize) -> Vec<Option<T>> {
+    rolling(values, period, |window| {
+        window.iter().skip(1).fold(window[0], |acc, v| acc.max(*v))
+    })
+}
+
+/// Lowest value of each trailing `period` window.
+pub fn lowest<T: Number>(values: &[T], period: usize) -> Vec<Option<T>> {
+    rolling(values, period, |window| {
+        window.iter().skip(1).fold(window[0], |acc, v| acc.min(*v))
+    })
+}
+
+/// Applies `f` to each trailing window of `period` values.
+pub(crate) fn rolling<T: Copy, U>(
+    values: &[T],
+    period: usize,
+    f: impl Fn(&[T]) -> U,
+) -> Vec<Option<U>> {
+    (0..values.len())
+        .map(|i| (period > 0 && i + 1 >= period).then(|| f(&values[i + 1 - period..=i])))
+        .collect()
+}
@@ -0,0 +1,391 @@
+//! Momentum oscillators. Outputs are aligned with the input, pair them with
+//! candle timestamps through [`with_timestamps`](super::with_timestamps).
+
+use exchange_outpost_abi::Candle;
+use serde::{Deserialize, Serialize};
+
+use super::ma::{rma, sma};
+use super::{Number, Source, highest, lowest, on_defined, rolling};
+
+/// How RSI averages gains and losses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Smoothing {
+    /// Wilder's recursive smoothing (RMA), the standard RSI definition.
+    #[default]
+    Wilder,
+    /// Simple average of the last `period` changes (Cutler's RSI).
+    Simple,
+}
+
+impl Smoothing {
+    fn apply<T: Number>(self, values: &[T], period: usize) -> Vec<Option<T>> {
+        match self {
+            Smoothing::Wilder => rma(values, period),
+            Smoothing::Simple => sma(values, period),
+        }
+    }
+}
+
+/// %K and %D lines of a stochastic oscillator.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Stochastic<T> {
+    pub k: Vec<Option<T>>,
+    pub d: Vec<Option<T>>,
+}
+
+fn hundred<T: Number>() -> T {
+    T::from_usize(100)
+}
+
+/// Relative strength index, 0 to 100.
+pub fn rsi<T: Number>(values: &[T], period: usize, smoothing: Smoothing) -> Vec<Option<T>> {
+    if values.len() < 2 {
+        return vec![None; values.len()];
+    }
+    let (gains, losses): (Vec<T>, Vec<T>) = values
+        .windows(2)
+        .map(|w| {
+            let change = w[1] - w[0];
+            if change > T::zero() {
+                (change, T::zero())
+            } else {
+                (T::zero(), -change)
+            }
+        })
+        .unzip();
+    let avg_gain = smoothing.apply(&gains, period);
+    let avg_loss = smoothing.apply(&losses, period);
+
+    let mut out = vec![None];
+    out.extend(avg_gain.iter().zip(&avg_loss).map(|(gain, loss)| {
+        let (gain, loss) = ((*gain)?, (*loss)?);
+        Some(if loss == T::zero() {
+            if gain == T::zero() {
+                T::from_usize(50)
+            } else {
+                hundred()
+            }
+        } else {
+            hundred::<T>() - hundred::<T>() / (T::one() + gain / loss)
+        })
+    }));
+    out
+}
+
+/// Position of `close` within the high/low range, 0 to 100; 50 for a flat range.
+fn raw_stochastic<T: Number>(high: &[T], low: &[T], close: &[T], period: usize) -> Vec<Option<T>> {
+    let highest = highest(high, period);
+    let lowest = lowest(low, period);
+    (0..close.len())
+        .map(|i| {
+            let (hh, ll) = (highest[i]?, lowest[i]?);
+            let range = hh - ll;
+            Some(if range > T::zero() {
+                hundred::<T>() * (close[i] - ll) / range
+            } else {
+                T::from_usize(50)
+            })
+        })
+        .collect()
+}
+
+fn stochastic_lines<T: Number>(
+    raw: &[Option<T>],
+    k_smoothing: usize,
+    d_period: usize,
+) -> Stochastic<T> {
+    let k = on_defined(raw, |v| sma(v, k_smoothing.max(1)));
+    let d = on_defined(&k, |v| sma(v, d_period.max(1)));
+    Stochastic { k, d }
+}
+
+/// Stochastic oscillator: raw %K over `k_period` bars, smoothed by an SMA of
+/// `k_smoothing` (1 for the fast stochastic), and %D as an SMA of %K.
+pub fn stochastic<T: Number>(
+    candles: &[Candle<T>],
+    k_period: usize,
+    k_smoothing: usize,
+    d_period: usize,
+) -> Stochastic<T> {
+    let raw = raw_stochastic(
+        &Source::High.values(candles),
+        &Source::Low.values(candles),
+        &Source::Close.values(candles),
+        k_period,
+    );
+    stochastic_lines(&raw, k_smoothing, d_period)
+}
+
+/// Stochastic oscillator applied to RSI values.
+pub fn stoch_rsi<T: Number>(
+    values: &[T],
+    rsi_period: usize,
+    stoch_period: usize,
+    k_smoothing: usize,
+    d_period: usize,
+) -> Stochastic<T> {
+    let rsi = rsi(values, rsi_period, Smoothing::Wilder);
+    let raw = on_defined(&rsi, |v| raw_stochastic(v, v, v, stoch_period));
+    stochastic_lines(&raw, k_smoothing, d_period)
+}
+
+/// Williams %R, -100 to 0.
+pub fn williams_r<T: Number>(candles: &[Candle<T>], period: usize) -> Vec<Option<T>> {
+    let highest = highest(&Source::High.values(candles), period);
+    let lowest = lowest(&Source::Low.values(candles), period);
+    candles
+        .iter()
+        .enumerate()
+        .map(|(i, candle)| {
+            let (hh, ll) = (highest[i]?, lowest[i]?);
+            let range = hh - ll;
+            Some(if range > T::zero() {
+                -hundred::<T>() * (hh - candle.close) / range
+            } else {
+                -T::from_usize(50)
+            })
+        })
+        .collect()
+}
+
+/// Commodity channel index over the typical price (HLC3).
+pub fn cci<T: Number>(candles: &[Candle<T>], period: usize) -> Vec<Option<T>> {
+    let typical = Source::Hlc3.values(candles);
+    let constant = T::from_f64(0.015);
+    rolling(&typical, period, |window| {
+        let n = T::from_usize(window.len());
+        let mean = window.iter().fold(T::zero(), |acc, v| acc + *v) / n;
+        let deviation = window
+            .iter()
+            .fold(T::zero(), |acc, v| acc + (*v - mean).abs())
+            / n;
+        let last = window[window.len() - 1];
+        if deviation > T::zero() {
+            (last - mean) / (constant * deviation)
+        } else {
+            T::zero()
+        }
+    })
+}
+
+/// Money flow index, 0 to 100: a volume-weighted RSI over the typical price.
+pub fn mfi<T: Number>(candles: &[Candle<T>], period: usize) -> Vec<Option<T>> {
+    let typical = Source::Hlc3.values(candles);
+    let flows: Vec<(T, T)> = (1..candles.len())
+        .map(|i| {
+            let flow = typical[i] * candles[i].volume;
+            if typical[i] > typical[i - 1] {
+                (flow, T::zero())
+            } else if typical[i] < typical[i - 1] {
+                (T::zero(), flow)
+            } else {
+                (T::zero(), T::zero())
+            }
+        })
+        .collect();
+    let mut out = vec![None; candles.len().min(1)];
+    out.extend(rolling(&flows, period, |window| {
+        let (positive, negative) = window
+            .iter()
+            .fold((T::zero(), T::zero()), |(p, n), (fp, fl)| {
+                (p + *fp, n + *fl)
+            });
+        if negative == T::zero() {
+            if positive == T::zero() {
+                T::from_usize(50)
+            } else {
+                hundred()
+            }
+        } else {
+            hundred::<T>() - hundred::<T>() / (T::one() + positive / negative)
+        }
+    }));
+    out
+}
+
+/// Rate of change in percent over `period` bars.
+pub fn roc<T: Number>(values: &[T], period: usize) -> Vec<Option<T>> {
+    (0..values.len())
+        .map(|i| {
+            if period == 0 || i < period || values[i - period] == T::zero() {
+                return None;
+            }
+            let base = values[i - period];
+            Some(hundred::<T>() * (values[i] - base) / base)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_series, candles, decimal_candles, decimals, padded, to_f64};
+
+    /// Closes from StockCharts' RSI worked example. Expected values follow
+    /// Wilder's definition without intermediate rounding.
+    const PRICES: [f64; 33] = [
+        44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03, 45.61,
+        46.28, 46.28, 46.00, 46.03, 46.41, 46.22, 45.64, 46.21, 46.25, 45.71, 46.45, 45.78, 45.35,
+        44.03, 44.18, 44.22, 44.57, 43.42, 42.66, 43.13,
+    ];
+
+    const WILDER_RSI: [f64; 19] = [
+        70.4641, 66.2496, 66.4809, 69.3469, 66.2947, 57.915, 62.8807, 63.2088, 56.0116, 62.3399,
+        54.671, 50.3868, 40.0194, 41.4926, 41.9024, 45.4995, 37.3228, 33.0905, 37.7888,
+    ];
+
+    /// `[open, high, low, close, volume]`
+    const ROWS: [[f64; 5]; 6] = [
+        [8.0, 10.0, 8.0, 9.0, 100.0],
+        [9.0, 12.0, 9.0, 11.0, 200.0],
+        [9.0, 11.0, 9.0, 10.0, 150.0],
+        [10.0, 13.0, 10.0, 12.0, 300.0],
+        [12.0, 14.0, 12.0, 13.0, 250.0],
+        [10.0, 12.0, 10.0, 11.0, 100.0],
+    ];
+
+    #[test]
+    fn wilder_rsi_matches_reference() {
+        assert_series(
+            &rsi(&PRICES, 14, Smoothing::Wilder),
+            &padded(14, &WILDER_RSI),
+            1e-4,
+        );
+    }
+
+    #[test]
+    fn simple_rsi_averages_the_window() {
+        let expected = padded(
+            14,
+            &[
+                70.4641, 70.021, 69.8312, 80.5677, 73.3333, 59.8063, 62.5282, 60.0, 48.4778,
+                53.8784, 48.9524, 43.8628, 37.7329, 32.2635, 32.7181, 38.1426, 31.7483, 25.0996,
+                30.2177,
+            ],
+        );
+        assert_series(&rsi(&PRICES, 14, Smoothing::Simple), &expected, 1e-4);
+    }
+
+    #[test]
+    fn rsi_without_losses() {
+        let rising = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_series(
+            &rsi(&rising, 3, Smoothing::Wilder),
+            &padded(3, &[100.0, 100.0]),
+            1e-12,
+        );
+        let flat = [5.0; 5];
+        assert_series(
+            &rsi(&flat, 3, Smoothing::Simple),
+            &padded(3, &[50.0, 50.0]),
+            1e-12,
+        );
+        assert_eq!(rsi(&[1.0], 3, Smoothing::Wilder), vec![None]);
+    }
+
+    #[test]
+    fn rsi_on_decimal() {
+        assert_series(
+            &to_f64(&rsi(&decimals(&PRICES), 14, Smoothing::Wilder)),
+            &padded(14, &WILDER_RSI),
+            1e-4,
+        );
+    }
+
+    #[test]
+    fn fast_and_slow_stochastic() {
+        let candles = candles(&ROWS);
+        let fast = stochastic(&candles, 3, 1, 2);
+        assert_series(&fast.k, &padded(2, &[50.0, 75.0, 80.0, 25.0]), 1e-12);
+        assert_series(&fast.d, &padded(3, &[62.5, 77.5, 52.5]), 1e-12);
+
+        let slow = stochastic(&candles, 3, 2, 2);
+        assert_series(&slow.k, &padded(3, &[62.5, 77.5, 52.5]), 1e-12);
+        assert_series(&slow.d, &padded(4, &[70.0, 65.0]), 1e-12);
+    }
+
+    #[test]
+    fn stochastic_and_williams_r_on_flat_range() {
+        let candles = candles(&[[5.0, 5.0, 5.0, 5.0, 1.0]; 4]);
+        assert_series(
+            &stochastic(&candles, 2, 1, 1).k,
+            &padded(1, &[50.0, 50.0, 50.0]),
+            1e-12,
+        );
+        assert_series(
+            &williams_r(&candles, 2),
+            &padded(1, &[-50.0, -50.0, -50.0]),
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn stoch_rsi_matches_reference() {
+        let result = stoch_rsi(&PRICES, 14, 10, 3, 3);
+        assert_series(
+            &result.k,
+            &padded(
+                25,
+                &[
+                    14.5957, 0.0, 1.8689, 4.5757, 12.4529, 10.584, 7.8773, 5.3543,
+                ],
+            ),
+            1e-4,
+        );
+        assert_series(
+            &result.d,
+            &padded(27, &[5.4882, 2.1482, 6.2992, 9.2042, 10.3047, 7.9385]),
+            1e-4,
+        );
+    }
+
+    #[test]
+    fn williams_r_matches_reference() {
+        assert_series(
+            &williams_r(&candles(&ROWS), 3),
+            &padded(2, &[-50.0, -25.0, -20.0, -75.0]),
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn cci_matches_reference() {
+        assert_series(
+            &cci(&candles(&ROWS), 3),
+            &padded(2, &[12.5, 100.0, 92.857142857, -80.0]),
+            1e-6,
+        );
+        let flat = candles(&[[5.0, 5.0, 5.0, 5.0, 1.0]; 3]);
+        assert_series(&cci(&flat, 3), &padded(2, &[0.0]), 1e-12);
+    }
+
+    #[test]
+    fn mfi_matches_reference() {
+        let expected = padded(3, &[78.971962617, 81.818181818, 85.987261146]);
+        assert_series(&mfi(&candles(&ROWS), 3), &expected, 1e-6);
+        assert_series(&to_f64(&mfi(&decimal_candles(&ROWS), 3)), &expected, 1e-6);
+    }
+
+    #[test]
+    fn mfi_without_negative_flow() {
+        let rising = candles(&[
+            [1.0, 1.0, 1.0, 1.0, 10.0],
+            [2.0, 2.0, 2.0, 2.0, 10.0],
+            [3.0, 3.0, 3.0, 3.0, 10.0],
+        ]);
+        assert_series(&mfi(&rising, 2), &padded(2, &[100.0]), 1e-12);
+        let flat = candles(&[[1.0, 1.0, 1.0, 1.0, 10.0]; 3]);
+        assert_series(&mfi(&flat, 2), &padded(2, &[50.0]), 1e-12);
+    }
+
+    #[test]
+    fn roc_in_percent() {
+        assert_series(
+            &roc(&[10.0, 11.0, 12.1, 0.0, 5.0], 1),
+            &[None, Some(10.0), Some(10.0), Some(-100.0), None],
+            1e-9,
+        );
+        assert!(roc(&[1.0, 2.0], 0).iter().all(Option::is_none));
+    }
+}
@@ -0,0 +1,476 @@
+//! Incremental indicators. Each indicator's fields are its whole state and
+//! derive serde, so a function can store it between invocations and feed
+//! only the candles that arrived since the last call.
+//!
+//! ```ignore
+//! let mut rsi: Stream<Rsi<f64>> = previous_state.unwrap_or_else(|| Stream::new(Rsi::new(14, Smoothing::Wilder, Source::Close)));
+//! let values = rsi.update_new(&candles);
+//! // persist `rsi` for the next invocation
+//! ```
+//!
+//! Outputs match the batch functions for the same candles.
+
+use std::collections::VecDeque;
+
+use exchange_outpost_abi::Candle;
+use serde::{Deserialize, Serialize};
+
+use super::oscillators::Smoothing;
+use super::volatility::Band;
+use super::{Number, Source};
+
+pub trait StreamingIndicator<T: Number> {
+    type Output;
+
+    /// Feeds the next candle; `None` while the indicator is warming up.
+    fn update(&mut self, candle: &Candle<T>) -> Option<Self::Output>;
+}
+
+/// Wraps an indicator with the timestamp of the last candle it consumed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stream<I> {
+    pub indicator: I,
+    pub last_timestamp: Option<i64>,
+}
+
+impl<I> Stream<I> {
+    pub fn new(indicator: I) -> Self {
+        Self {
+            indicator,
+            last_timestamp: None,
+        }
+    }
+
+    /// Feeds the candles newer than the last one consumed, returning their
+    /// timestamps and outputs.
+    pub fn update_new<T: Number>(&mut self, candles: &[Candle<T>]) -> Vec<(i64, Option<I::Output>)>
+    where
+        I: StreamingIndicator<T>,
+    {
+        let start = match self.last_timestamp {
+            Some(last) => candles.partition_point(|c| c.timestamp <= last),
+            None => 0,
+        };
+        let outputs: Vec<_> = candles[start..]
+            .iter()
+            .map(|candle| (candle.timestamp, self.indicator.update(candle)))
+            .collect();
+        if let Some((timestamp, _)) = outputs.last() {
+            self.last_timestamp = Some(*timestamp);
+        }
+        outputs
+    }
+}
+
+/// Mean of the last `period` values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RollingMean<T> {
+    period: usize,
+    window: VecDeque<T>,
+    sum: T,
+}
+
+impl<T: Number> RollingMean<T> {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            window: VecDeque::with_capacity(period),
+            sum: T::zero(),
+        }
+    }
+
+    pub fn next(&mut self, value: T) -> Option<T> {
+        if self.period == 0 {
+            return None;
+        }
+        self.window.push_back(value);
+        self.sum = self.sum + value;
+        if self.window.len() > self.period {
+            let oldest = self.window.pop_front()?;
+            self.sum = self.sum - oldest;
+        }
+        (self.window.len() == self.period).then(|| self.sum / T::from_usize(self.period))
+    }
+}
+
+/// Exponential smoothing seeded with the SMA of the first `period` values,
+/// matching [`ema`](super::ma::ema) and [`rma`](super::ma::rma).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpSmoothing<T> {
+    period: usize,
+    alpha: T,
+    count: usize,
+    seed: T,
+    value: Option<T>,
+}
+
+impl<T: Number> ExpSmoothing<T> {
+    pub fn ema(period: usize) -> Self {
+        Self::with_alpha(period, T::from_usize(2) / T::from_usize(period + 1))
+    }
+
+    pub fn wilder(period: usize) -> Self {
+        Self::with_alpha(period, T::one() / T::from_usize(period.max(1)))
+    }
+
+    fn with_alpha(period: usize, alpha: T) -> Self {
+        Self {
+            period,
+            alpha,
+            count: 0,
+            seed: T::zero(),
+            value: None,
+        }
+    }
+
+    pub fn next(&mut self, value: T) -> Option<T> {
+        if self.period == 0 {
+            return None;
+        }
+        self.value = match self.value {
+            Some(prev) => Some(prev + self.alpha * (value - prev)),
+            None => {
+                self.count += 1;
+                self.seed = self.seed + value;
+                (self.count == self.period).then(|| self.seed / T::from_usize(self.period))
+            }
+        };
+        self.value
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sma<T> {
+    source: Source,
+    mean: RollingMean<T>,
+}
+
+impl<T: Number> Sma<T> {
+    pub fn new(period: usize, source: Source) -> Self {
+        Self {
+            source,
+            mean: RollingMean::new(period),
+        }
+    }
+}
+
+impl<T: Number> StreamingIndicator<T> for Sma<T> {
+    type Output = T;
+
+    fn update(&mut self, candle: &Candle<T>) -> Option<T> {
+        self.mean.next(self.source.value(candle))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ema<T> {
+    source: Source,
+    smoothing: ExpSmoothing<T>,
+}
+
+impl<T: Number> Ema<T> {
+    pub fn new(period: usize, source: Source) -> Self {
+        Self {
+            source,
+            smoothing: ExpSmoothing::ema(period),
+        }
+    }
+}
+
+impl<T: Number> StreamingIndicator<T> for Ema<T> {
+    type Output = T;
+
+    fn update(&mut self, candle: &Candle<T>) -> Option<T> {
+        self.smoothing.next(self.source.value(candle))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Averager<T> {
+    Wilder(ExpSmoothing<T>),
+    Simple(RollingMean<T>),
+}
+
+impl<T: Number> Averager<T> {
+    fn new(smoothing: Smoothing, period: usize) -> Self {
+        match smoothing {
+            Smoothing::Wilder => Averager::Wilder(ExpSmoothing::wilder(period)),
+            Smoothing::Simple => Averager::Simple(RollingMean::new(period)),
+        }
+    }
+
+    fn next(&mut self, value: T) -> Option<T> {
+        match self {
+            Averager::Wilder(inner) => inner.next(value),
+            Averager::Simple(inner) => inner.next(value),
+        }
+    }
+}
+
+/// Streaming counterpart of [`rsi`](super::oscillators::rsi).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rsi<T> {
+    source: Source,
+    previous: Option<T>,
+    gain: Averager<T>,
+    loss: Averager<T>,
+}
+
+impl<T: Number> Rsi<T> {
+    pub fn new(period: usize, smoothing: Smoothing, source: Source) -> Self {
+        Self {
+            source,
+            previous: None,
+            gain: Averager::new(smoothing, period),
+            loss: Averager::new(smoothing, period),
+        }
+    }
+}
+
+impl<T: Number> StreamingIndicator<T> for Rsi<T> {
+    type Output = T;
+
+    fn update(&mut self, candle: &Candle<T>) -> Option<T> {
+        let value = self.source.value(candle);
+        let previous = self.previous.replace(value)?;
+        let change = value - previous;
+        let (gain, loss) = if change > T::zero() {
+            (change, T::zero())
+        } else {
+            (T::zero(), -change)
+        };
+        let (gain, loss) = (self.gain.next(gain), self.loss.next(loss));
+        let (gain, loss) = (gain?, loss?);
+        let hundred = T::from_usize(100);
+        Some(if loss == T::zero() {
+            if gain == T::zero() {
+                T::from_usize(50)
+            } else {
+                hundred
+            }
+        } else {
+            hundred - hundred / (T::one() + gain / loss)
+        })
+    }
+}
+
+/// Streaming counterpart of [`atr`](super::volatility::atr).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Atr<T> {
+    previous_close: Option<T>,
+    smoothing: ExpSmoothing<T>,
+}
+
+impl<T: Number> Atr<T> {
+    pub fn new(period: usize) -> Self {
+        Self {
+            previous_close: None,
+            smoothing: ExpSmoothing::wilder(period),
+        }
+    }
+}
+
+impl<T: Number> StreamingIndicator<T> for Atr<T> {
+    type Output = T;
+
+    fn update(&mut self, candle: &Candle<T>) -> Option<T> {
+        let range = candle.high - candle.low;
+        let true_range = match self.previous_close.replace(candle.close) {
+            Some(prev) => range
+                .max((candle.high - prev).abs())
+                .max((candle.low - prev).abs()),
+            None => range,
+        };
+        self.smoothing.next(true_range)
+    }
+}
+
+/// Streaming counterpart of [`bollinger`](super::volatility::bollinger).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bollinger<T> {
+    period: usize,
+    multiplier: T,
+    source: Source,
+    window: VecDeque<T>,
+}
+
+impl<T: Number> Bollinger<T> {
+    pub fn new(period: usize, multiplier: T, source: Source) -> Self {
+        Self {
+            period,
+            multiplier,
+            source,
+            window: VecDeque::with_capacity(period),
+        }
+    }
+}
+
+impl<T: Number> StreamingIndicator<T> for Bollinger<T> {
+    type Output = Band<T>;
+
+    fn update(&mut self, candle: &Candle<T>) -> Option<Band<T>> {
+        if self.period == 0 {
+            return None;
+        }
+        let price = self.source.value(candle);
+        self.window.push_back(price);
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+        if self.window.len() < self.period {
+            return None;
+        }
+        let n = T::from_usize(self.period);
+        let mean = self.window.iter().fold(T::zero(), |acc, v| acc + *v) / n;
+        let variance = self
+            .window
+            .iter()
+            .fold(T::zero(), |acc, v| acc + (*v - mean) * (*v - mean))
+            / n;
+        let offset = self.multiplier * variance.sqrt();
+        Some(Band::new(mean + offset, mean, mean - offset, price))
+    }
+}
+
+/// Streaming counterpart of [`obv`](super::volume::obv).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Obv<T> {
+    previous_close: Option<T>,
+    total: T,
+}
+
+impl<T: Number> Default for Obv<T> {
+    fn default() -> Self {
+        Self {
+            previous_close: None,
+            total: T::zero(),
+        }
+    }
+}
+
+impl<T: Number> StreamingIndicator<T> for Obv<T> {
+    type Output = T;
+
+    fn update(&mut self, candle: &Candle<T>) -> Option<T> {
+        if let Some(prev) = self.previous_close.replace(candle.close) {
+            if candle.close > prev {
+                self.total = self.total + candle.volume;
+            } else if candle.close < prev {
+                self.total = self.total - candle.volume;
+            }
+        }
+        Some(self.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::DeserializeOwned;
+
+    use super::*;
+    use crate::indicators::ma::{ema, sma};
+    use crate::indicators::oscillators::rsi;
+    use crate::indicators::volatility::{atr, bollinger};
+    use crate::indicators::volume::obv;
+    use crate::testing::{assert_series, candles};
+
+    /// Splits inside the warm-up of every indicator below.
+    const SPLIT: usize = 10;
+
+    fn series() -> Vec<Candle<f64>> {
+        let rows: Vec<[f64; 5]> = (0..30)
+            .map(|i| {
+                let close = 10.0 + ((i * 7) % 11) as f64 * 0.5;
+                let volume = 100.0 + ((i * 13) % 17) as f64;
+                [close - 0.25, close + 1.0, close - 0.75, close, volume]
+            })
+            .collect();
+        candles(&rows)
+    }
+
+    /// Feeds `candles[..SPLIT]`, round-trips the stream through JSON as a
+    /// function would between invocations, then feeds the whole series again.
+    fn in_two_chunks<I>(indicator: I, candles: &[Candle<f64>]) -> Vec<Option<I::Output>>
+    where
+        I: StreamingIndicator<f64> + Serialize + DeserializeOwned,
+    {
+        let mut stream = Stream::new(indicator);
+        let mut outputs = stream.update_new(&candles[..SPLIT]);
+        assert_eq!(stream.last_timestamp, Some(candles[SPLIT - 1].timestamp));
+
+        let json = serde_json::to_string(&stream).unwrap();
+        let mut stream: Stream<I> = serde_json::from_str(&json).unwrap();
+        outputs.extend(stream.update_new(candles));
+        assert!(stream.update_new(candles).is_empty());
+
+        let timestamps: Vec<i64> = outputs.iter().map(|(t, _)| *t).collect();
+        let expected: Vec<i64> = candles.iter().map(|c| c.timestamp).collect();
+        assert_eq!(timestamps, expected);
+        outputs.into_iter().map(|(_, output)| output).collect()
+    }
+
+    #[test]
+    fn sma_and_ema_match_batch() {
+        let candles = series();
+        let closes = Source::Close.values(&candles);
+        assert_series(
+            &in_two_chunks(Sma::new(14, Source::Close), &candles),
+            &sma(&closes, 14),
+            1e-9,
+        );
+        assert_series(
+            &in_two_chunks(Ema::new(14, Source::Close), &candles),
+            &ema(&closes, 14),
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn rsi_matches_batch() {
+        let candles = series();
+        let closes = Source::Close.values(&candles);
+        for smoothing in [Smoothing::Wilder, Smoothing::Simple] {
+            assert_series(
+                &in_two_chunks(Rsi::new(14, smoothing, Source::Close), &candles),
+                &rsi(&closes, 14, smoothing),
+                1e-9,
+            );
+        }
+    }
+
+    #[test]
+    fn atr_matches_batch() {
+        let candles = series();
+        assert_series(
+            &in_two_chunks(Atr::new(14), &candles),
+            &atr(&candles, 14),
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn bollinger_matches_batch() {
+        let candles = series();
+        let streamed = in_two_chunks(Bollinger::new(20, 2.0, Source::Close), &candles);
+        let batch = bollinger(&Source::Close.values(&candles), 20, 2.0);
+        let field = |bands: &[Option<Band<f64>>], f: fn(&Band<f64>) -> f64| -> Vec<Option<f64>> {
+            bands.iter().map(|b| b.as_ref().map(f)).collect()
+        };
+        for f in [
+            |b: &Band<f64>| b.upper,
+            |b: &Band<f64>| b.middle,
+            |b: &Band<f64>| b.lower,
+            |b: &Band<f64>| b.percent_b,
+        ] {
+            assert_series(&field(&streamed, f), &field(&batch, f), 1e-9);
+        }
+    }
+
+    #[test]
+    fn obv_matches_batch() {
+        let candles = series();
+        let batch: Vec<Option<f64>> = obv(&candles).into_iter().map(Some).collect();
+        assert_series(&in_two_chunks(Obv::default(), &candles), &batch, 1e-9);
+    }
+}
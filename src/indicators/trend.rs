@@ -0,0 +1,518 @@
+//! Trend direction and strength indicators. Each bar carries the indicator
+//! values plus the trend [`Direction`] they imply.
+
+use exchange_outpost_abi::Candle;
+use serde::{Deserialize, Serialize};
+
+use super::ma::rma;
+use super::volatility::{atr, true_range};
+use super::{Number, Source, on_defined, rolling};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+/// Directional movement index with ADX.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Dmi<T> {
+    pub plus_di: T,
+    pub minus_di: T,
+    /// `None` until the DX series has warmed up, `period - 1` bars after the DIs.
+    pub adx: Option<T>,
+    pub direction: Direction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Aroon<T> {
+    pub up: T,
+    pub down: T,
+    /// `up - down`
+    pub oscillator: T,
+    pub direction: Direction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ParabolicSar<T> {
+    pub sar: T,
+    pub direction: Direction,
+    /// True on the bar where the SAR flipped sides.
+    pub reversed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SuperTrend<T> {
+    /// Active band: the lower band in an uptrend, the upper band in a downtrend.
+    pub value: T,
+    pub direction: Direction,
+    pub reversed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Vortex<T> {
+    pub plus: T,
+    pub minus: T,
+    pub direction: Direction,
+}
+
+fn direction<T: Number>(up: T, down: T) -> Direction {
+    if up >= down {
+        Direction::Up
+    } else {
+        Direction::Down
+    }
+}
+
+/// +DI/-DI and ADX with Wilder smoothing over `period`.
+pub fn dmi<T: Number>(candles: &[Candle<T>], period: usize) -> Vec<Option<Dmi<T>>> {
+    if candles.len() < 2 {
+        return vec![None; candles.len()];
+    }
+    let tr = true_range(candles);
+    let (plus_dm, minus_dm): (Vec<T>, Vec<T>) = candles
+        .windows(2)
+        .map(|w| {
+            let up = w[1].high - w[0].high;
+            let down = w[0].low - w[1].low;
+            (
+                if up > down && up > T::zero() {
+                    up
+                } else {
+                    T::zero()
+                },
+                if down > up && down > T::zero() {
+                    down
+                } else {
+                    T::zero()
+                },
+            )
+        })
+        .unzip();
+    let smoothed_tr = rma(&tr[1..], period);
+    let smoothed_plus = rma(&plus_dm, period);
+    let smoothed_minus = rma(&minus_dm, period);
+
+    let hundred = T::from_usize(100);
+    let di: Vec<Option<(T, T)>> = (0..plus_dm.len())
+        .map(|i| {
+            let tr = smoothed_tr[i]?;
+            if tr == T::zero() {
+                return Some((T::zero(), T::zero()));
+            }
+            Some((
+                hundred * smoothed_plus[i]? / tr,
+                hundred * smoothed_minus[i]? / tr,
+            ))
+        })
+        .collect();
+    let dx: Vec<Option<T>> = di
+        .iter()
+        .map(|v| {
+            let (plus, minus) = (*v)?;
+            let sum = plus + minus;
+            Some(if sum == T::zero() {
+                T::zero()
+            } else {
+                hundred * (plus - minus).abs() / sum
+            })
+        })
+        .collect();
+    let adx = on_defined(&dx, |v| rma(v, period));
+
+    let mut out = vec![None];
+    out.extend(di.iter().zip(adx).map(|(di, adx)| {
+        let (plus_di, minus_di) = (*di)?;
+        Some(Dmi {
+            plus_di,
+            minus_di,
+            adx,
+            direction: direction(plus_di, minus_di),
+        })
+    }));
+    out
+}
+
+/// Aroon up/down, 0 to 100, from the bars since the highest high and lowest
+/// low within the last `period + 1` bars.
+pub fn aroon<T: Number>(candles: &[Candle<T>], period: usize) -> Vec<Option<Aroon<T>>> {
+    if period == 0 {
+        return vec![None; candles.len()];
+    }
+    let since_extreme = |values: &[T], better: fn(T, T) -> bool| {
+        let mut best = 0;
+        for (i, value) in values.iter().enumerate() {
+            if better(*value, values[best]) || *value == values[best] {
+                best = i;
+            }
+        }
+        values.len() - 1 - best
+    };
+    let highs = Source::High.values(candles);
+    let lows = Source::Low.values(candles);
+    let n = T::from_usize(period);
+    let hundred = T::from_usize(100);
+    let since_high = rolling(&highs, period + 1, |w| since_extreme(w, |a, b| a > b));
+    let since_low = rolling(&lows, period + 1, |w| since_extreme(w, |a, b| a < b));
+    since_high
+        .iter()
+        .zip(&since_low)
+        .map(|(high, low)| {
+            let up = hundred * (n - T::from_usize((*high)?)) / n;
+            let down = hundred * (n - T::from_usize((*low)?)) / n;
+            Some(Aroon {
+                up,
+                down,
+                oscillator: up - down,
+                direction: direction(up, down),
+            })
+        })
+        .collect()
+}
+
+/// Parabolic SAR with acceleration `step` capped at `max_step` (commonly
+/// 0.02 and 0.2). The first bar has no value.
+pub fn parabolic_sar<T: Number>(
+    candles: &[Candle<T>],
+    step: T,
+    max_step: T,
+) -> Vec<Option<ParabolicSar<T>>> {
+    let mut out = vec![None; candles.len()];
+    if candles.len() < 2 {
+        return out;
+    }
+    let mut up = candles[1].close >= candles[0].close;
+    let (mut sar, mut extreme) = if up {
+        (candles[0].low, candles[1].high)
+    } else {
+        (candles[0].high, candles[1].low)
+    };
+    let mut af = step;
+    out[1] = Some(ParabolicSar {
+        sar,
+        direction: if up { Direction::Up } else { Direction::Down },
+        reversed: false,
+    });
+
+    for i in 2..candles.len() {
+        let candle = &candles[i];
+        sar = sar + af * (extreme - sar);
+        let mut reversed = false;
+        if up {
+            sar = sar.min(candles[i - 1].low).min(candles[i - 2].low);
+            if candle.low < sar {
+                (up, reversed, sar, extreme, af) = (false, true, extreme, candle.low, step);
+            } else if candle.high > extreme {
+                extreme = candle.high;
+                af = (af + step).min(max_step);
+            }
+        } else {
+            sar = sar.max(candles[i - 1].high).max(candles[i - 2].high);
+            if candle.high > sar {
+                (up, reversed, sar, extreme, af) = (true, true, extreme, candle.high, step);
+            } else if candle.low < extreme {
+                extreme = candle.low;
+                af = (af + step).min(max_step);
+            }
+        }
+        out[i] = Some(ParabolicSar {
+            sar,
+            direction: if up { Direction::Up } else { Direction::Down },
+            reversed,
+        });
+    }
+    out
+}
+
+/// SuperTrend: HL2 bands `multiplier` ATRs wide that ratchet with the trend
+/// and flip when the close crosses the active band.
+pub fn super_trend<T: Number>(
+    candles: &[Candle<T>],
+    period: usize,
+    multiplier: T,
+) -> Vec<Option<SuperTrend<T>>> {
+    let atr = atr(candles, period);
+    let mut out = vec![None; candles.len()];
+    let mut state: Option<(T, T, Direction)> = None;
+    for (i, candle) in candles.iter().enumerate() {
+        let Some(atr) = atr[i] else { continue };
+        let hl2 = Source::Hl2.value(candle);
+        let basic_upper = hl2 + multiplier * atr;
+        let basic_lower = hl2 - multiplier * atr;
+        let (upper, lower, direction, reversed) = match state {
+            None => (basic_upper, basic_lower, Direction::Up, false),
+            Some((prev_upper, prev_lower, prev_direction)) => {
+                let prev_close = candles[i - 1].close;
+                let upper = if basic_upper < prev_upper || prev_close > prev_upper {
+                    basic_upper
+                } else {
+                    prev_upper
+                };
+                let lower = if basic_lower > prev_lower || prev_close < prev_lower {
+                    basic_lower
+                } else {
+                    prev_lower
+                };
+                let direction = match prev_direction {
+                    Direction::Up if candle.close < lower => Direction::Down,
+                    Direction::Down if candle.close > upper => Direction::Up,
+                    unchanged => unchanged,
+                };
+                (upper, lower, direction, direction != prev_direction)
+            }
+        };
+        state = Some((upper, lower, direction));
+        out[i] = Some(SuperTrend {
+            value: match direction {
+                Direction::Up => lower,
+                Direction::Down => upper,
+            },
+            direction,
+            reversed,
+        });
+    }
+    out
+}
+
+/// Vortex indicator VI+ and VI- over `period` bars.
+pub fn vortex<T: Number>(candles: &[Candle<T>], period: usize) -> Vec<Option<Vortex<T>>> {
+    if candles.len() < 2 {
+        return vec![None; candles.len()];
+    }
+    let tr = true_range(candles);
+    let movements: Vec<(T, T, T)> = (1..candles.len())
+        .map(|i| {
+            (
+                (candles[i].high - candles[i - 1].low).abs(),
+                (candles[i].low - candles[i - 1].high).abs(),
+                tr[i],
+            )
+        })
+        .collect();
+    let mut out = vec![None];
+    out.extend(
+        rolling(&movements, period, |window| {
+            let (plus, minus, tr) = window.iter().fold(
+                (T::zero(), T::zero(), T::zero()),
+                |(p, m, t), (vp, vm, vt)| (p + *vp, m + *vm, t + *vt),
+            );
+            if tr == T::zero() {
+                return None;
+            }
+            let (plus, minus) = (plus / tr, minus / tr);
+            Some(Vortex {
+                plus,
+                minus,
+                direction: direction(plus, minus),
+            })
+        })
+        .into_iter()
+        .map(Option::flatten),
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Direction::{Down, Up};
+    use super::*;
+    use crate::testing::{assert_near, assert_series, candles, decimal_candles, padded};
+
+    /// Rallies into bar 5, sells off to bar 9 and recovers.
+    /// `[open, high, low, close, volume]`
+    const ROWS: [[f64; 5]; 12] = [
+        [10.0, 10.5, 9.5, 10.0, 1.0],
+        [10.0, 11.0, 9.8, 10.8, 1.0],
+        [10.8, 11.6, 10.5, 11.4, 1.0],
+        [11.4, 12.3, 11.2, 12.1, 1.0],
+        [12.1, 12.8, 11.8, 12.6, 1.0],
+        [12.6, 13.0, 12.0, 12.2, 1.0],
+        [12.2, 12.4, 11.2, 11.4, 1.0],
+        [11.4, 11.6, 10.6, 10.8, 1.0],
+        [10.8, 11.0, 10.0, 10.2, 1.0],
+        [10.2, 10.6, 9.6, 10.4, 1.0],
+        [10.4, 11.2, 10.2, 11.0, 1.0],
+        [11.0, 11.9, 10.8, 11.7, 1.0],
+    ];
+
+    fn directions<I>(series: &[Option<I>], f: impl Fn(&I) -> Direction) -> Vec<Option<Direction>> {
+        series.iter().map(|v| v.as_ref().map(&f)).collect()
+    }
+
+    fn reversals<I>(series: &[Option<I>], f: impl Fn(&I) -> bool) -> Vec<usize> {
+        (0..series.len())
+            .filter(|&i| series[i].as_ref().is_some_and(&f))
+            .collect()
+    }
+
+    #[test]
+    fn dmi_matches_reference() {
+        let plus_di = padded(
+            3,
+            &[
+                52.941176, 52.040816, 41.958042, 26.785714, 18.447348, 12.575321, 8.511393,
+                25.321709, 38.735732,
+            ],
+        );
+        let minus_di = padded(
+            3,
+            &[
+                0.0, 0.0, 0.0, 24.107143, 35.280553, 43.14907, 42.131395, 28.376079, 18.441567,
+            ],
+        );
+        let adx = padded(
+            5,
+            &[
+                100.0, 68.421053, 56.057526, 55.660353, 59.235754, 41.386527, 39.42215,
+            ],
+        );
+
+        let result = dmi(&candles(&ROWS), 3);
+        let field = |f: fn(&Dmi<f64>) -> Option<f64>| -> Vec<Option<f64>> {
+            result.iter().map(|v| v.as_ref().and_then(f)).collect()
+        };
+        assert_series(&field(|d| Some(d.plus_di)), &plus_di, 1e-5);
+        assert_series(&field(|d| Some(d.minus_di)), &minus_di, 1e-5);
+        assert_series(&field(|d| d.adx), &adx, 1e-5);
+        assert_eq!(
+            directions(&result, |d| d.direction),
+            [None, None, None]
+                .into_iter()
+                .chain([Up, Up, Up, Up, Down, Down, Down, Down, Up].map(Some))
+                .collect::<Vec<_>>()
+        );
+
+        let decimal = dmi(&decimal_candles(&ROWS), 3);
+        let last = decimal[11].unwrap();
+        assert_near(last.plus_di.to_f64(), 38.735732, 1e-5);
+        assert_near(last.minus_di.to_f64(), 18.441567, 1e-5);
+        assert_near(last.adx.unwrap().to_f64(), 39.42215, 1e-5);
+    }
+
+    #[test]
+    fn dmi_needs_two_bars() {
+        assert_eq!(dmi(&candles(&ROWS[..1]), 3), vec![None]);
+    }
+
+    #[test]
+    fn aroon_counts_bars_since_extremes() {
+        let result = aroon(&candles(&ROWS), 4);
+        let field = |f: fn(&Aroon<f64>) -> f64| -> Vec<Option<f64>> {
+            result.iter().map(|v| v.as_ref().map(f)).collect()
+        };
+        assert_series(
+            &field(|a| a.up),
+            &padded(4, &[100.0, 100.0, 75.0, 50.0, 25.0, 0.0, 0.0, 100.0]),
+            1e-12,
+        );
+        assert_series(
+            &field(|a| a.down),
+            &padded(4, &[0.0, 0.0, 0.0, 100.0, 100.0, 100.0, 75.0, 50.0]),
+            1e-12,
+        );
+        assert_series(
+            &field(|a| a.oscillator),
+            &padded(4, &[100.0, 100.0, 75.0, -50.0, -75.0, -100.0, -75.0, 50.0]),
+            1e-12,
+        );
+        assert_eq!(result[7].unwrap().direction, Down);
+        assert_eq!(result[11].unwrap().direction, Up);
+
+        let decimal = aroon(&decimal_candles(&ROWS), 4);
+        assert_eq!(decimal[9].unwrap().oscillator.to_f64(), -100.0);
+        assert!(aroon(&candles(&ROWS), 0).iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn parabolic_sar_reverses_on_penetration() {
+        let result = parabolic_sar(&candles(&ROWS), 0.02, 0.2);
+        assert_series(
+            &result.iter().map(|v| v.map(|p| p.sar)).collect::<Vec<_>>(),
+            &padded(
+                1,
+                &[
+                    9.5, 9.5, 9.584, 9.74696, 9.991203, 10.292083, 10.562875, 13.0, 12.94, 12.8064,
+                    12.678144,
+                ],
+            ),
+            1e-6,
+        );
+        assert_eq!(reversals(&result, |p| p.reversed), vec![8]);
+        assert_eq!(result[7].unwrap().direction, Up);
+        assert_eq!(result[8].unwrap().direction, Down);
+        assert_eq!(result[11].unwrap().direction, Down);
+
+        let decimal = parabolic_sar(
+            &decimal_candles(&ROWS),
+            Number::from_f64(0.02),
+            Number::from_f64(0.2),
+        );
+        assert_near(decimal[11].unwrap().sar.to_f64(), 12.678144, 1e-6);
+        assert!(decimal[8].unwrap().reversed);
+    }
+
+    #[test]
+    fn super_trend_flips_when_close_crosses_the_band() {
+        let result = super_trend(&candles(&ROWS), 3, 1.0);
+        assert_series(
+            &result
+                .iter()
+                .map(|v| v.map(|s| s.value))
+                .collect::<Vec<_>>(),
+            &padded(
+                2,
+                &[
+                    9.95, 10.65, 11.233333, 11.455556, 12.896296, 12.164198, 11.542798, 11.128532,
+                    11.128532, 10.303986,
+                ],
+            ),
+            1e-6,
+        );
+        assert_eq!(
+            directions(&result, |s| s.direction),
+            [None, None]
+                .into_iter()
+                .chain([Up, Up, Up, Up, Down, Down, Down, Down, Down, Up].map(Some))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(reversals(&result, |s| s.reversed), vec![6, 11]);
+
+        let decimal = super_trend(&decimal_candles(&ROWS), 3, Number::one());
+        assert_near(decimal[10].unwrap().value.to_f64(), 11.128532, 1e-6);
+        assert!(decimal[11].unwrap().reversed);
+    }
+
+    #[test]
+    fn vortex_matches_reference() {
+        let result = vortex(&candles(&ROWS), 3);
+        let field = |f: fn(&Vortex<f64>) -> f64| -> Vec<Option<f64>> {
+            result.iter().map(|v| v.as_ref().map(f)).collect()
+        };
+        assert_series(
+            &field(|v| v.plus),
+            &padded(
+                3,
+                &[
+                    1.5, 1.625, 1.483871, 1.0, 0.625, 0.375, 0.466667, 0.866667, 1.258065,
+                ],
+            ),
+            1e-6,
+        );
+        assert_series(
+            &field(|v| v.minus),
+            &padded(
+                3,
+                &[
+                    0.470588, 0.4375, 0.548387, 0.96875, 1.375, 1.625, 1.6, 1.133333, 0.709677,
+                ],
+            ),
+            1e-6,
+        );
+        assert_eq!(result[6].unwrap().direction, Up);
+        assert_eq!(result[7].unwrap().direction, Down);
+
+        let decimal = vortex(&decimal_candles(&ROWS), 3);
+        assert_near(decimal[11].unwrap().minus.to_f64(), 0.709677, 1e-6);
+    }
+}
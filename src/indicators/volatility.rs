@@ -0,0 +1,413 @@
+//! Volatility measures and price channels.
+
+use exchange_outpost_abi::Candle;
+use serde::Serialize;
+
+use super::ma::{ema, rma};
+use super::{Number, Source, highest, lowest, rolling, zip_with};
+
+/// One bar of a price channel.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Band<T> {
+    pub upper: T,
+    pub middle: T,
+    pub lower: T,
+    /// `(upper - lower) / middle`, zero when `middle` is zero.
+    pub width: T,
+    /// Position of the price within the band: 0 at `lower`, 1 at `upper`.
+    pub percent_b: T,
+}
+
+impl<T: Number> Band<T> {
+    pub(crate) fn new(upper: T, middle: T, lower: T, price: T) -> Self {
+        let range = upper - lower;
+        Band {
+            upper,
+            middle,
+            lower,
+            width: if middle == T::zero() {
+                T::zero()
+            } else {
+                range / middle
+            },
+            percent_b: if range == T::zero() {
+                T::from_f64(0.5)
+            } else {
+                (price - lower) / range
+            },
+        }
+    }
+}
+
+/// Chandelier exit stop levels for long and short positions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ChandelierExit<T> {
+    pub long_stop: T,
+    pub short_stop: T,
+}
+
+/// True range; the first bar uses its high-low range.
+pub fn true_range<T: Number>(candles: &[Candle<T>]) -> Vec<T> {
+    candles
+        .iter()
+        .enumerate()
+        .map(|(i, candle)| {
+            let range = candle.high - candle.low;
+            match i.checked_sub(1).map(|p| candles[p].close) {
+                Some(prev_close) => range
+                    .max((candle.high - prev_close).abs())
+                    .max((candle.low - prev_close).abs()),
+                None => range,
+            }
+        })
+        .collect()
+}
+
+/// Average true range with Wilder smoothing.
+pub fn atr<T: Number>(candles: &[Candle<T>], period: usize) -> Vec<Option<T>> {
+    rma(&true_range(candles), period)
+}
+
+/// Bollinger bands: SMA of `period` values with bands `multiplier`
+/// population standard deviations away.
+pub fn bollinger<T: Number>(values: &[T], period: usize, multiplier: T) -> Vec<Option<Band<T>>> {
+    let stats = rolling(values, period, |window| {
+        let n = T::from_usize(window.len());
+        let mean = window.iter().fold(T::zero(), |acc, v| acc + *v) / n;
+        let variance = window
+            .iter()
+            .fold(T::zero(), |acc, v| acc + (*v - mean) * (*v - mean))
+            / n;
+        (mean, variance.sqrt())
+    });
+    stats
+        .iter()
+        .zip(values)
+        .map(|(stat, price)| {
+            let (mean, deviation) = (*stat)?;
+            let offset = multiplier * deviation;
+            Some(Band::new(mean + offset, mean, mean - offset, *price))
+        })
+        .collect()
+}
+
+/// Keltner channel: EMA of the typical price with bands `multiplier` ATRs away.
+pub fn keltner<T: Number>(
+    candles: &[Candle<T>],
+    ema_period: usize,
+    atr_period: usize,
+    multiplier: T,
+) -> Vec<Option<Band<T>>> {
+    let middle = ema(&Source::Hlc3.values(candles), ema_period);
+    let atr = atr(candles, atr_period);
+    zip_with(&middle, &atr, |m, a| (m, a))
+        .into_iter()
+        .zip(candles)
+        .map(|(value, candle)| {
+            let (middle, atr) = value?;
+            let offset = multiplier * atr;
+            Some(Band::new(
+                middle + offset,
+                middle,
+                middle - offset,
+                candle.close,
+            ))
+        })
+        .collect()
+}
+
+/// Donchian channel: highest high and lowest low of the last `period` bars.
+pub fn donchian<T: Number>(candles: &[Candle<T>], period: usize) -> Vec<Option<Band<T>>> {
+    let upper = highest(&Source::High.values(candles), period);
+    let lower = lowest(&Source::Low.values(candles), period);
+    zip_with(&upper, &lower, |u, l| (u, l))
+        .into_iter()
+        .zip(candles)
+        .map(|(value, candle)| {
+            let (upper, lower) = value?;
+            let middle = (upper + lower) / T::from_usize(2);
+            Some(Band::new(upper, middle, lower, candle.close))
+        })
+        .collect()
+}
+
+/// Chandelier exit: `multiplier` ATRs below the highest high (long) and
+/// above the lowest low (short) of the last `period` bars.
+pub fn chandelier_exit<T: Number>(
+    candles: &[Candle<T>],
+    period: usize,
+    multiplier: T,
+) -> Vec<Option<ChandelierExit<T>>> {
+    let highest = highest(&Source::High.values(candles), period);
+    let lowest = lowest(&Source::Low.values(candles), period);
+    let atr = atr(candles, period);
+    (0..candles.len())
+        .map(|i| {
+            let offset = multiplier * atr[i]?;
+            Some(ChandelierExit {
+                long_stop: highest[i]? - offset,
+                short_stop: lowest[i]? + offset,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::testing::{assert_series, candles, decimal_candles, decimals, padded, to_f64};
+
+    /// `[open, high, low, close, volume]`
+    const ROWS: [[f64; 5]; 8] = [
+        [10.0, 11.0, 9.0, 10.5, 100.0],
+        [10.5, 12.0, 10.0, 11.5, 100.0],
+        [11.5, 12.5, 11.0, 12.0, 100.0],
+        [12.0, 14.0, 11.5, 13.5, 100.0],
+        [13.5, 13.5, 12.0, 12.5, 100.0],
+        [12.5, 13.0, 10.5, 11.0, 100.0],
+        [11.0, 11.5, 10.0, 10.5, 100.0],
+        [10.5, 12.0, 10.5, 11.5, 100.0],
+    ];
+
+    const ATR: [f64; 6] = [
+        1.833333333,
+        2.055555556,
+        1.870370370,
+        2.080246914,
+        1.886831276,
+        1.757887517,
+    ];
+
+    fn closes() -> Vec<f64> {
+        ROWS.iter().map(|row| row[3]).collect()
+    }
+
+    /// One field of every defined band, as `f64`.
+    fn field<T: Number>(bands: &[Option<Band<T>>], f: impl Fn(&Band<T>) -> T) -> Vec<Option<f64>> {
+        bands
+            .iter()
+            .map(|b| b.as_ref().map(|b| f(b).to_f64()))
+            .collect()
+    }
+
+    #[track_caller]
+    fn assert_bands<T: Number>(bands: &[Option<Band<T>>], expected: &[[f64; 5]], warm_up: usize) {
+        let column = |i: usize| -> Vec<f64> { expected.iter().map(|row| row[i]).collect() };
+        assert_series(
+            &field(bands, |b| b.upper),
+            &padded(warm_up, &column(0)),
+            1e-6,
+        );
+        assert_series(
+            &field(bands, |b| b.middle),
+            &padded(warm_up, &column(1)),
+            1e-6,
+        );
+        assert_series(
+            &field(bands, |b| b.lower),
+            &padded(warm_up, &column(2)),
+            1e-6,
+        );
+        assert_series(
+            &field(bands, |b| b.width),
+            &padded(warm_up, &column(3)),
+            1e-6,
+        );
+        assert_series(
+            &field(bands, |b| b.percent_b),
+            &padded(warm_up, &column(4)),
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn true_range_uses_previous_close() {
+        assert_eq!(
+            true_range(&candles(&ROWS)),
+            vec![2.0, 2.0, 1.5, 2.5, 1.5, 2.5, 1.5, 1.5]
+        );
+    }
+
+    #[test]
+    fn atr_matches_reference() {
+        assert_series(&atr(&candles(&ROWS), 3), &padded(2, &ATR), 1e-6);
+        assert_series(
+            &to_f64(&atr(&decimal_candles(&ROWS), 3)),
+            &padded(2, &ATR),
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn bollinger_matches_reference() {
+        let expected = [
+            [
+                12.580552462,
+                11.333333333,
+                10.086114204,
+                0.220097493,
+                0.767261242,
+            ],
+            [
+                14.033006505,
+                12.333333333,
+                10.633660162,
+                0.275622676,
+                0.843203236,
+            ],
+            [
+                13.913885796,
+                12.666666667,
+                11.419447538,
+                0.196929336,
+                0.433184690,
+            ],
+            [
+                14.388138001,
+                12.333333333,
+                10.278528666,
+                0.333211568,
+                0.175557158,
+            ],
+            [
+                13.033006505,
+                11.333333333,
+                9.633660162,
+                0.299942324,
+                0.254854831,
+            ],
+            [11.816496581, 11.0, 10.183503419, 0.148453924, 0.806186218],
+        ];
+        assert_bands(&bollinger(&closes(), 3, 2.0), &expected, 2);
+        assert_bands(
+            &bollinger(&decimals(&closes()), 3, Decimal::from_usize(2)),
+            &expected,
+            2,
+        );
+    }
+
+    #[test]
+    fn bollinger_on_flat_and_zero_prices() {
+        let flat = bollinger(&[0.0; 3], 3, 2.0);
+        assert_eq!(
+            flat[2],
+            Some(Band {
+                upper: 0.0,
+                middle: 0.0,
+                lower: 0.0,
+                width: 0.0,
+                percent_b: 0.5,
+            })
+        );
+    }
+
+    #[test]
+    fn keltner_matches_reference() {
+        let expected = [
+            [
+                14.722222222,
+                11.055555556,
+                7.388888889,
+                0.663316583,
+                0.628787879,
+            ],
+            [
+                16.138888889,
+                12.027777778,
+                7.916666667,
+                0.683602771,
+                0.679054054,
+            ],
+            [
+                16.087962963,
+                12.347222222,
+                8.606481481,
+                0.605924259,
+                0.520420792,
+            ],
+            [
+                16.084104938,
+                11.923611111,
+                7.763117284,
+                0.697858021,
+                0.389002226,
+            ],
+            [
+                15.068801440,
+                11.295138889,
+                7.521476337,
+                0.668192324,
+                0.394646265,
+            ],
+            [
+                14.830011145,
+                11.314236111,
+                7.798461077,
+                0.621478109,
+                0.526418626,
+            ],
+        ];
+        assert_bands(&keltner(&candles(&ROWS), 3, 3, 2.0), &expected, 2);
+        assert_bands(
+            &keltner(&decimal_candles(&ROWS), 3, 3, Decimal::from_usize(2)),
+            &expected,
+            2,
+        );
+    }
+
+    #[test]
+    fn donchian_matches_reference() {
+        let expected = [
+            [12.5, 10.75, 9.0, 0.325581395, 0.857142857],
+            [14.0, 12.0, 10.0, 0.333333333, 0.875],
+            [14.0, 12.5, 11.0, 0.24, 0.5],
+            [14.0, 12.25, 10.5, 0.285714286, 0.142857143],
+            [13.5, 11.75, 10.0, 0.297872340, 0.142857143],
+            [13.0, 11.5, 10.0, 0.260869565, 0.5],
+        ];
+        assert_bands(&donchian(&candles(&ROWS), 3), &expected, 2);
+        assert_bands(&donchian(&decimal_candles(&ROWS), 3), &expected, 2);
+    }
+
+    #[track_caller]
+    fn assert_stops<T: Number>(exits: &[Option<ChandelierExit<T>>], long: &[f64], short: &[f64]) {
+        let long_stops: Vec<_> = exits
+            .iter()
+            .map(|e| e.map(|e| e.long_stop.to_f64()))
+            .collect();
+        let short_stops: Vec<_> = exits
+            .iter()
+            .map(|e| e.map(|e| e.short_stop.to_f64()))
+            .collect();
+        assert_series(&long_stops, &padded(2, long), 1e-6);
+        assert_series(&short_stops, &padded(2, short), 1e-6);
+    }
+
+    #[test]
+    fn chandelier_exit_matches_reference() {
+        let long = [
+            7.0,
+            7.833333333,
+            8.388888889,
+            7.759259259,
+            7.839506173,
+            7.726337449,
+        ];
+        let short = [
+            14.5,
+            16.166666667,
+            16.611111111,
+            16.740740741,
+            15.660493827,
+            15.273662551,
+        ];
+        assert_stops(&chandelier_exit(&candles(&ROWS), 3, 3.0), &long, &short);
+        assert_stops(
+            &chandelier_exit(&decimal_candles(&ROWS), 3, Decimal::from_usize(3)),
+            &long,
+            &short,
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Volume-based indicators.
+
+use exchange_outpost_abi::Candle;
+use serde::{Deserialize, Serialize};
+
+use super::ma::ema;
+use super::{Number, Source, rolling, zip_with};
+
+/// Where an anchored VWAP starts accumulating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Anchor {
+    /// First candle at or after this timestamp.
+    Timestamp(i64),
+    /// Candle at this index.
+    Index(usize),
+}
+
+/// On-balance volume, starting from zero at the first candle.
+pub fn obv<T: Number>(candles: &[Candle<T>]) -> Vec<T> {
+    let mut total = T::zero();
+    candles
+        .iter()
+        .enumerate()
+        .map(|(i, candle)| {
+            if let Some(prev) = i.checked_sub(1).map(|p| &candles[p]) {
+                if candle.close > prev.close {
+                    total = total + candle.volume;
+                } else if candle.close < prev.close {
+                    total = total - candle.volume;
+                }
+            }
+            total
+        })
+        .collect()
+}
+
+/// Cumulative VWAP of `source` that restarts whenever `reset(i)` is true.
+fn vwap_with<T: Number>(
+    candles: &[Candle<T>],
+    source: Source,
+    start: usize,
+    reset: impl Fn(usize) -> bool,
+) -> Vec<Option<T>> {
+    let mut out = vec![None; candles.len()];
+    let (mut price_volume, mut volume) = (T::zero(), T::zero());
+    for i in start..candles.len() {
+        if i > start && reset(i) {
+            (price_volume, volume) = (T::zero(), T::zero());
+        }
+        let candle = &candles[i];
+        price_volume = price_volume + source.value(candle) * candle.volume;
+        volume = volume + candle.volume;
+        out[i] = Some(if volume == T::zero() {
+            source.value(candle)
+        } else {
+            price_volume / volume
+        });
+    }
+    out
+}
+
+/// VWAP that resets at each session boundary. Sessions are consecutive
+/// windows of `session_length`, shifted by `offset`, both in the same unit
+/// as the candle timestamps (e.g. `86_400` for UTC days on second timestamps).
+pub fn session_vwap<T: Number>(
+    candles: &[Candle<T>],
+    session_length: i64,
+    offset: i64,
+    source: Source,
+) -> Vec<Option<T>> {
+    let session = |i: usize| (candles[i].timestamp - offset).div_euclid(session_length.max(1));
+    vwap_with(candles, source, 0, |i| session(i) != session(i - 1))
+}
+
+/// VWAP accumulated from `anchor` onwards; `None` before the anchor.
+pub fn anchored_vwap<T: Number>(
+    candles: &[Candle<T>],
+    anchor: Anchor,
+    source: Source,
+) -> Vec<Option<T>> {
+    let start = match anchor {
+        Anchor::Index(index) => index,
+        Anchor::Timestamp(timestamp) => candles
+            .iter()
+            .position(|c| c.timestamp >= timestamp)
+            .unwrap_or(candles.len()),
+    };
+    vwap_with(candles, source, start, |_| false)
+}
+
+/// Close location value times volume, the money flow volume of a bar.
+fn money_flow_volume<T: Number>(candle: &Candle<T>) -> T {
+    let range = candle.high - candle.low;
+    if range == T::zero() {
+        return T::zero();
+    }
+    let clv = ((candle.close - candle.low) - (candle.high - candle.close)) / range;
+    clv * candle.volume
+}
+
+/// Accumulation/distribution line.
+pub fn accumulation_distribution<T: Number>(candles: &[Candle<T>]) -> Vec<T> {
+    let mut total = T::zero();
+    candles
+        .iter()
+        .map(|candle| {
+            total = total + money_flow_volume(candle);
+            total
+        })
+        .collect()
+}
+
+/// Chaikin money flow over `period` bars, -1 to 1.
+pub fn chaikin_money_flow<T: Number>(candles: &[Candle<T>], period: usize) -> Vec<Option<T>> {
+    let flows: Vec<(T, T)> = candles
+        .iter()
+        .map(|c| (money_flow_volume(c), c.volume))
+        .collect();
+    rolling(&flows, period, |window| {
+        let (flow, volume) = window
+            .iter()
+            .fold((T::zero(), T::zero()), |(f, v), (wf, wv)| {
+                (f + *wf, v + *wv)
+            });
+        if volume == T::zero() {
+            T::zero()
+        } else {
+            flow / volume
+        }
+    })
+}
+
+/// Percentage difference between a fast and a slow EMA of volume.
+pub fn volume_oscillator<T: Number>(
+    candles: &[Candle<T>],
+    fast: usize,
+    slow: usize,
+) -> Vec<Option<T>> {
+    let volume: Vec<T> = candles.iter().map(|c| c.volume).collect();
+    let fast = ema(&volume, fast);
+    let slow = ema(&volume, slow);
+    zip_with(&fast, &slow, |f, s| {
+        if s == T::zero() {
+            T::zero()
+        } else {
+            T::from_usize(100) * (f - s) / s
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_series, candles_at, decimal_candles, to_f64};
+
+    const CLOSES: [f64; 6] = [10.0, 12.0, 11.0, 13.0, 14.0, 12.0];
+    const VOLUMES: [f64; 6] = [1.0, 3.0, 2.0, 2.0, 1.0, 3.0];
+    const TIMESTAMPS: [i64; 6] = [0, 30, 90, 120, 200, 230];
+
+    fn rows() -> Vec<[f64; 5]> {
+        CLOSES
+            .iter()
+            .zip(VOLUMES)
+            .map(|(&close, volume)| [close, close + 1.0, close - 1.0, close, volume])
+            .collect()
+    }
+
+    fn series() -> Vec<Candle<f64>> {
+        let rows: Vec<(i64, [f64; 5])> = TIMESTAMPS.into_iter().zip(rows()).collect();
+        candles_at(&rows)
+    }
+
+    fn some(values: &[f64]) -> Vec<Option<f64>> {
+        values.iter().copied().map(Some).collect()
+    }
+
+    #[test]
+    fn obv_adds_up_and_down_volume() {
+        assert_eq!(obv(&series()), vec![0.0, 3.0, 1.0, 3.0, 4.0, 1.0]);
+    }
+
+    #[test]
+    fn session_vwap_resets_at_each_boundary() {
+        assert_series(
+            &session_vwap(&series(), 100, 0, Source::Close),
+            &some(&[10.0, 11.5, 68.0 / 6.0, 13.0, 14.0, 12.5]),
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn session_vwap_applies_the_offset() {
+        // Sessions start at 50, 150, ... so the first two candles belong to
+        // the session that began at -50.
+        assert_series(
+            &session_vwap(&series(), 100, 50, Source::Close),
+            &some(&[10.0, 11.5, 11.0, 12.0, 14.0, 12.5]),
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn anchored_vwap_from_an_index() {
+        assert_series(
+            &anchored_vwap(&series(), Anchor::Index(2), Source::Close),
+            &[None, None, Some(11.0), Some(12.0), Some(12.4), Some(12.25)],
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn anchored_vwap_from_a_timestamp() {
+        assert_series(
+            &anchored_vwap(&series(), Anchor::Timestamp(100), Source::Close),
+            &[
+                None,
+                None,
+                None,
+                Some(13.0),
+                Some(40.0 / 3.0),
+                Some(76.0 / 6.0),
+            ],
+            1e-12,
+        );
+        assert_series(
+            &anchored_vwap(&series(), Anchor::Timestamp(120), Source::Close),
+            &[
+                None,
+                None,
+                None,
+                Some(13.0),
+                Some(40.0 / 3.0),
+                Some(76.0 / 6.0),
+            ],
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn anchor_past_the_end_yields_nothing() {
+        let candles = series();
+        for anchor in [Anchor::Index(6), Anchor::Index(100), Anchor::Timestamp(231)] {
+            assert_eq!(
+                anchored_vwap(&candles, anchor, Source::Close),
+                vec![None; candles.len()]
+            );
+        }
+    }
+
+    #[test]
+    fn vwap_without_volume_falls_back_to_the_price() {
+        let candles = candles_at(&[
+            (0, [5.0, 6.0, 4.0, 5.0, 0.0]),
+            (60, [6.0, 7.0, 5.0, 6.0, 0.0]),
+        ]);
+        assert_series(
+            &session_vwap(&candles, 3600, 0, Source::Close),
+            &some(&[5.0, 6.0]),
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn decimal_matches_f64() {
+        let candles = decimal_candles(&rows());
+        assert_eq!(
+            to_f64(&obv(&candles).into_iter().map(Some).collect::<Vec<_>>()),
+            some(&[0.0, 3.0, 1.0, 3.0, 4.0, 1.0])
+        );
+        assert_series(
+            &to_f64(&anchored_vwap(&candles, Anchor::Index(2), Source::Close)),
+            &[None, None, Some(11.0), Some(12.0), Some(12.4), Some(12.25)],
+            1e-12,
+        );
+    }
+}
@@ -1,12 +1,44 @@
+extern crate self as rust_function_template;
+
+use arguments::CallArguments;
+use error::FunctionError;
 use exchange_outpost_abi::FunctionArgs;
-use extism_pdk::{FnResult, Json, ToBytes, encoding, plugin_fn};
-use serde::Serialize;
+use extism_pdk::{FnResult, Json, ToBytes, encoding};
+use serde::{Deserialize, Serialize};
+
+pub mod alerts;
+pub mod arguments;
+pub mod error;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod harness;
+pub mod host;
+pub mod indicators;
+pub mod manifest;
+pub mod patterns;
+pub mod state;
+#[cfg(test)]
+mod testing;
+pub mod validation;
+
+pub const FUNCTION_NAME: &str = "Rust Function Template";
+pub const DESCRIPTION: &str = "A template for creating Rust functions";
+pub const FINANCIAL_DATA_KEYS: &[&str] = &[];
+pub const ENFORCE_SCHEMAS: bool = false;
 
-#[derive(Serialize, ToBytes)]
+#[derive(Debug, Deserialize, CallArguments)]
+pub struct Arguments {}
+
+#[derive(Debug, Serialize, ToBytes)]
 #[encoding(Json)]
 pub struct Output {}
 
-#[plugin_fn]
+#[cfg_attr(target_arch = "wasm32", extism_pdk::plugin_fn)]
 pub fn run(call_args: FunctionArgs) -> FnResult<Output> {
+    Ok(execute(&call_args)?)
+}
+
+pub fn execute(call_args: &FunctionArgs) -> Result<Output, FunctionError> {
+    validation::validate_financial_data(call_args, FINANCIAL_DATA_KEYS)?;
+    let _arguments = Arguments::from_call_args(call_args)?;
     Ok(Output {})
 }
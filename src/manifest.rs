@@ -0,0 +1,45 @@
+//! `manifest.json` generated from the constants and `Arguments` type in
+//! `lib.rs`. Written and checked by `cargo xtask manifest`.
+
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::ser::PrettyFormatter;
+
+use crate::arguments::CallArguments;
+use crate::{Arguments, DESCRIPTION, ENFORCE_SCHEMAS, FINANCIAL_DATA_KEYS, FUNCTION_NAME};
+
+#[derive(Debug, Serialize)]
+pub struct Manifest {
+    pub function_name: &'static str,
+    pub description: &'static str,
+    pub financial_data_keys: &'static [&'static str],
+    pub call_arguments_schema: Value,
+    pub enforce_schemas: bool,
+}
+
+pub fn manifest() -> Manifest {
+    Manifest {
+        function_name: FUNCTION_NAME,
+        description: DESCRIPTION,
+        financial_data_keys: FINANCIAL_DATA_KEYS,
+        call_arguments_schema: Arguments::schema(),
+        enforce_schemas: ENFORCE_SCHEMAS,
+    }
+}
+
+impl Manifest {
+    pub fn to_value(&self) -> Value {
+        serde_json::to_value(self).expect("manifest serializes to json")
+    }
+
+    /// Renders the manifest with the 4-space indentation used by `manifest.json`.
+    pub fn to_json_pretty(&self) -> String {
+        let mut out = Vec::new();
+        let mut serializer =
+            serde_json::Serializer::with_formatter(&mut out, PrettyFormatter::with_indent(b"    "));
+        self.serialize(&mut serializer)
+            .expect("manifest serializes to json");
+        out.push(b'\n');
+        String::from_utf8(out).expect("json is utf-8")
+    }
+}
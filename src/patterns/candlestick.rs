@@ -0,0 +1,596 @@
+//! Candlestick patterns. Shapes are judged by body and shadow sizes relative
+//! to the candle's range, with thresholds in [`CandlestickConfig`].
+
+use exchange_outpost_abi::Candle;
+use serde::{Deserialize, Serialize};
+
+use super::Bias;
+use crate::indicators::Number;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandlestickPattern {
+    Doji,
+    Marubozu,
+    Hammer,
+    HangingMan,
+    InvertedHammer,
+    ShootingStar,
+    BullishEngulfing,
+    BearishEngulfing,
+    BullishHarami,
+    BearishHarami,
+    PiercingLine,
+    DarkCloudCover,
+    MorningStar,
+    EveningStar,
+    ThreeWhiteSoldiers,
+    ThreeBlackCrows,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PatternMatch {
+    pub pattern: CandlestickPattern,
+    /// Index of the last candle of the pattern.
+    pub index: usize,
+    pub timestamp: i64,
+    pub direction: Bias,
+    /// How clearly the candles match, 0 to 1.
+    pub strength: f64,
+}
+
+/// Thresholds as fractions of a candle's high-low range unless noted.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CandlestickConfig {
+    /// Largest body for a doji.
+    pub doji_body: f64,
+    /// Smallest body for a "long" candle (engulfing, soldiers, marubozu, stars).
+    pub long_body: f64,
+    /// Largest body for a small candle (hammer family, harami inner, star).
+    pub small_body: f64,
+    /// Smallest long shadow for the hammer family, as a multiple of the body.
+    pub long_shadow: f64,
+    /// Largest opposite shadow for the hammer family and marubozu.
+    pub short_shadow: f64,
+    /// Bars used to judge the prior trend for hammer/hanging man and friends.
+    pub trend_lookback: usize,
+}
+
+impl Default for CandlestickConfig {
+    fn default() -> Self {
+        Self {
+            doji_body: 0.1,
+            long_body: 0.6,
+            small_body: 0.35,
+            long_shadow: 2.0,
+            short_shadow: 0.1,
+            trend_lookback: 5,
+        }
+    }
+}
+
+/// A candle reduced to the proportions pattern rules look at.
+#[derive(Debug, Clone, Copy)]
+struct Shape {
+    open: f64,
+    close: f64,
+    range: f64,
+    body: f64,
+    upper: f64,
+    lower: f64,
+}
+
+impl Shape {
+    fn new<T: Number>(candle: &Candle<T>) -> Self {
+        let (open, high, low, close) = (
+            candle.open.to_f64(),
+            candle.high.to_f64(),
+            candle.low.to_f64(),
+            candle.close.to_f64(),
+        );
+        Self {
+            open,
+            close,
+            range: high - low,
+            body: (close - open).abs(),
+            upper: high - open.max(close),
+            lower: open.min(close) - low,
+        }
+    }
+
+    fn bullish(&self) -> bool {
+        self.close > self.open
+    }
+
+    fn bearish(&self) -> bool {
+        self.close < self.open
+    }
+
+    fn body_top(&self) -> f64 {
+        self.open.max(self.close)
+    }
+
+    fn body_bottom(&self) -> f64 {
+        self.open.min(self.close)
+    }
+
+    fn body_ratio(&self) -> f64 {
+        if self.range > 0.0 {
+            self.body / self.range
+        } else {
+            0.0
+        }
+    }
+
+    fn midpoint(&self) -> f64 {
+        (self.open + self.close) / 2.0
+    }
+}
+
+/// Limits a strength to 0..=1. Non-finite ratios, e.g. from a zero
+/// threshold, carry no information and count as no strength at all.
+fn clamp(strength: f64) -> f64 {
+    if strength.is_finite() {
+        strength.clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Scans every candle and returns the patterns that complete on it, in candle order.
+pub fn scan<T: Number>(candles: &[Candle<T>], config: &CandlestickConfig) -> Vec<PatternMatch> {
+    let shapes: Vec<Shape> = candles.iter().map(Shape::new).collect();
+    let mut matches = Vec::new();
+    for index in 0..candles.len() {
+        let mut push = |pattern, direction, strength: f64| {
+            matches.push(PatternMatch {
+                pattern,
+                index,
+                timestamp: candles[index].timestamp,
+                direction,
+                strength: clamp(strength),
+            })
+        };
+        let trend = prior_trend(&shapes, index, config.trend_lookback);
+        single(&shapes[index], trend, config, &mut push);
+        if index >= 1 {
+            double(&shapes[index - 1], &shapes[index], config, &mut push);
+        }
+        if index >= 2 {
+            triple(&shapes[index - 2..=index], config, &mut push);
+        }
+    }
+    matches
+}
+
+/// Direction of the closes over the `lookback` bars before `index`.
+fn prior_trend(shapes: &[Shape], index: usize, lookback: usize) -> Bias {
+    let Some(start) = index.checked_sub(lookback) else {
+        return Bias::Neutral;
+    };
+    if lookback == 0 {
+        return Bias::Neutral;
+    }
+    let (from, to) = (shapes[start].close, shapes[index - 1].close);
+    if to > from {
+        Bias::Bullish
+    } else if to < from {
+        Bias::Bearish
+    } else {
+        Bias::Neutral
+    }
+}
+
+fn single(
+    c: &Shape,
+    trend: Bias,
+    config: &CandlestickConfig,
+    push: &mut impl FnMut(CandlestickPattern, Bias, f64),
+) {
+    if c.range <= 0.0 {
+        return;
+    }
+    let ratio = c.body_ratio();
+    if ratio <= config.doji_body {
+        push(
+            CandlestickPattern::Doji,
+            Bias::Neutral,
+            1.0 - ratio / config.doji_body,
+        );
+        return;
+    }
+    let short = config.short_shadow * c.range;
+    if ratio >= config.long_body && c.upper <= short && c.lower <= short {
+        let direction = if c.bullish() {
+            Bias::Bullish
+        } else {
+            Bias::Bearish
+        };
+        push(CandlestickPattern::Marubozu, direction, ratio);
+        return;
+    }
+    if ratio > config.small_body {
+        return;
+    }
+    let long = config.long_shadow * c.body;
+    if c.lower >= long && c.upper <= short {
+        let strength = c.lower / c.range;
+        match trend {
+            Bias::Bearish => push(CandlestickPattern::Hammer, Bias::Bullish, strength),
+            Bias::Bullish => push(CandlestickPattern::HangingMan, Bias::Bearish, strength),
+            Bias::Neutral => {}
+        }
+    } else if c.upper >= long && c.lower <= short {
+        let strength = c.upper / c.range;
+        match trend {
+            Bias::Bearish => push(CandlestickPattern::InvertedHammer, Bias::Bullish, strength),
+            Bias::Bullish => push(CandlestickPattern::ShootingStar, Bias::Bearish, strength),
+            Bias::Neutral => {}
+        }
+    }
+}
+
+fn double(
+    prev: &Shape,
+    c: &Shape,
+    config: &CandlestickConfig,
+    push: &mut impl FnMut(CandlestickPattern, Bias, f64),
+) {
+    if prev.body <= 0.0 || c.body <= 0.0 {
+        return;
+    }
+    let engulfs = c.body_top() >= prev.body_top()
+        && c.body_bottom() <= prev.body_bottom()
+        && c.body > prev.body;
+    if engulfs && prev.bearish() && c.bullish() {
+        push(
+            CandlestickPattern::BullishEngulfing,
+            Bias::Bullish,
+            c.body / prev.body / 2.0,
+        );
+    } else if engulfs && prev.bullish() && c.bearish() {
+        push(
+            CandlestickPattern::BearishEngulfing,
+            Bias::Bearish,
+            c.body / prev.body / 2.0,
+        );
+    }
+
+    let inside = c.body_top() < prev.body_top() && c.body_bottom() > prev.body_bottom();
+    let long_prev = prev.body_ratio() >= config.long_body;
+    if inside && long_prev && c.body <= config.small_body * prev.range {
+        let strength = 1.0 - c.body / prev.body;
+        if prev.bearish() {
+            push(CandlestickPattern::BullishHarami, Bias::Bullish, strength);
+        } else if prev.bullish() {
+            push(CandlestickPattern::BearishHarami, Bias::Bearish, strength);
+        }
+    }
+
+    let long_both = long_prev && c.body_ratio() >= config.long_body;
+    if long_both && prev.bearish() && c.bullish() {
+        // Opens below the prior close and closes above its midpoint, inside its body.
+        if c.open < prev.close && c.close > prev.midpoint() && c.close < prev.open {
+            let strength = (c.close - prev.midpoint()) / (prev.open - prev.midpoint());
+            push(CandlestickPattern::PiercingLine, Bias::Bullish, strength);
+        }
+    } else if long_both
+        && prev.bullish()
+        && c.bearish()
+        && c.open > prev.close
+        && c.close < prev.midpoint()
+        && c.close > prev.open
+    {
+        let strength = (prev.midpoint() - c.close) / (prev.midpoint() - prev.open);
+        push(CandlestickPattern::DarkCloudCover, Bias::Bearish, strength);
+    }
+}
+
+fn triple(
+    window: &[Shape],
+    config: &CandlestickConfig,
+    push: &mut impl FnMut(CandlestickPattern, Bias, f64),
+) {
+    let (first, star, last) = (&window[0], &window[1], &window[2]);
+    let long = |s: &Shape| s.body_ratio() >= config.long_body;
+    let small_star = star.body <= config.small_body * first.range.max(star.range);
+
+    if long(first) && small_star && long(last) {
+        if first.bearish()
+            && last.bullish()
+            && star.body_top() <= first.close
+            && last.close > first.midpoint()
+        {
+            let strength = (last.close - first.midpoint()) / (first.open - first.midpoint());
+            push(CandlestickPattern::MorningStar, Bias::Bullish, strength);
+        } else if first.bullish()
+            && last.bearish()
+            && star.body_bottom() >= first.close
+            && last.close < first.midpoint()
+        {
+            let strength = (first.midpoint() - last.close) / (first.midpoint() - first.open);
+            push(CandlestickPattern::EveningStar, Bias::Bearish, strength);
+        }
+    }
+
+    if window.iter().all(long) {
+        let strength = window.iter().map(Shape::body_ratio).sum::<f64>() / 3.0;
+        let rising = window.windows(2).all(|w| {
+            w[1].close > w[0].close && w[1].open > w[0].body_bottom() && w[1].open < w[0].body_top()
+        });
+        let falling = window.windows(2).all(|w| {
+            w[1].close < w[0].close && w[1].open > w[0].body_bottom() && w[1].open < w[0].body_top()
+        });
+        if rising && window.iter().all(Shape::bullish) {
+            push(
+                CandlestickPattern::ThreeWhiteSoldiers,
+                Bias::Bullish,
+                strength,
+            );
+        } else if falling && window.iter().all(Shape::bearish) {
+            push(CandlestickPattern::ThreeBlackCrows, Bias::Bearish, strength);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CandlestickPattern::*;
+    use super::*;
+    use crate::testing::{assert_near, candles, decimal_candles};
+
+    /// Five falling closes, so the next candle follows a downtrend.
+    const DOWN: [[f64; 4]; 5] = [
+        [110.0, 111.0, 107.0, 108.0],
+        [108.0, 109.0, 105.0, 106.0],
+        [106.0, 107.0, 103.0, 104.0],
+        [104.0, 104.5, 101.0, 102.0],
+        [102.0, 102.5, 99.0, 100.0],
+    ];
+
+    /// Five rising closes.
+    const UP: [[f64; 4]; 5] = [
+        [100.0, 103.0, 99.0, 102.0],
+        [102.0, 105.0, 101.0, 104.0],
+        [104.0, 107.0, 103.0, 106.0],
+        [106.0, 109.0, 105.0, 108.0],
+        [108.0, 111.0, 107.0, 110.0],
+    ];
+
+    const HAMMER: [f64; 4] = [99.0, 100.2, 95.0, 100.0];
+    const INVERTED: [f64; 4] = [99.0, 104.0, 98.8, 100.0];
+    const LONG_BEAR: [f64; 4] = [110.0, 110.5, 99.5, 100.0];
+    const LONG_BULL: [f64; 4] = [100.0, 110.5, 99.5, 110.0];
+
+    /// Patterns completing on the last of `rows` (`[open, high, low, close]`).
+    fn found(rows: &[[f64; 4]]) -> Vec<(CandlestickPattern, Bias)> {
+        found_with(rows, &CandlestickConfig::default())
+            .into_iter()
+            .map(|m| (m.pattern, m.direction))
+            .collect()
+    }
+
+    fn found_with(rows: &[[f64; 4]], config: &CandlestickConfig) -> Vec<PatternMatch> {
+        let rows: Vec<[f64; 5]> = rows.iter().map(|&[o, h, l, c]| [o, h, l, c, 1.0]).collect();
+        let last = rows.len() - 1;
+        scan(&candles(&rows), config)
+            .into_iter()
+            .filter(|m| m.index == last)
+            .collect()
+    }
+
+    fn after(prefix: &[[f64; 4]], rows: &[[f64; 4]]) -> Vec<[f64; 4]> {
+        prefix.iter().chain(rows).copied().collect()
+    }
+
+    #[track_caller]
+    fn assert_found(rows: &[[f64; 4]], pattern: CandlestickPattern, direction: Bias) {
+        let found = found(rows);
+        assert!(found.contains(&(pattern, direction)), "{found:?}");
+    }
+
+    #[track_caller]
+    fn assert_not_found(rows: &[[f64; 4]], pattern: CandlestickPattern) {
+        let found = found(rows);
+        assert!(found.iter().all(|(p, _)| *p != pattern), "{found:?}");
+    }
+
+    #[test]
+    fn doji() {
+        assert_found(&[[100.0, 101.0, 99.0, 100.05]], Doji, Bias::Neutral);
+        assert_not_found(&[[100.0, 101.0, 99.0, 100.5]], Doji);
+    }
+
+    #[test]
+    fn marubozu() {
+        assert_found(&[[100.0, 110.0, 100.0, 110.0]], Marubozu, Bias::Bullish);
+        assert_found(&[[110.0, 110.0, 100.0, 100.0]], Marubozu, Bias::Bearish);
+        assert_not_found(&[[100.0, 110.0, 97.0, 110.0]], Marubozu);
+    }
+
+    #[test]
+    fn hammer_and_hanging_man() {
+        assert_found(&after(&DOWN, &[HAMMER]), Hammer, Bias::Bullish);
+        assert_not_found(&after(&DOWN, &[[99.0, 101.5, 95.0, 100.0]]), Hammer);
+
+        assert_found(&after(&UP, &[HAMMER]), HangingMan, Bias::Bearish);
+        assert_not_found(&after(&DOWN, &[HAMMER]), HangingMan);
+    }
+
+    #[test]
+    fn inverted_hammer_and_shooting_star() {
+        assert_found(&after(&DOWN, &[INVERTED]), InvertedHammer, Bias::Bullish);
+        assert_not_found(&after(&DOWN, &[[99.0, 104.0, 98.8, 101.0]]), InvertedHammer);
+
+        assert_found(&after(&UP, &[INVERTED]), ShootingStar, Bias::Bearish);
+        assert_not_found(&after(&DOWN, &[INVERTED]), ShootingStar);
+    }
+
+    #[test]
+    fn hammer_family_needs_a_prior_trend() {
+        let flat = [[100.0, 101.0, 99.0, 100.5]; 5];
+        assert!(found(&after(&flat, &[HAMMER])).is_empty());
+        assert!(found(&[HAMMER]).is_empty());
+    }
+
+    #[test]
+    fn engulfing() {
+        let bear = [102.0, 103.0, 99.5, 100.0];
+        assert_found(
+            &[bear, [99.5, 103.5, 99.0, 103.0]],
+            BullishEngulfing,
+            Bias::Bullish,
+        );
+        assert_not_found(&[bear, [100.5, 103.5, 100.0, 103.0]], BullishEngulfing);
+
+        let bull = [100.0, 103.0, 99.5, 102.0];
+        assert_found(
+            &[bull, [102.5, 103.5, 99.0, 99.5]],
+            BearishEngulfing,
+            Bias::Bearish,
+        );
+        assert_not_found(&[bull, [101.5, 103.5, 99.0, 99.5]], BearishEngulfing);
+    }
+
+    #[test]
+    fn harami() {
+        assert_found(
+            &[LONG_BEAR, [103.0, 106.0, 102.0, 105.0]],
+            BullishHarami,
+            Bias::Bullish,
+        );
+        assert_not_found(&[LONG_BEAR, [102.0, 109.0, 101.0, 108.0]], BullishHarami);
+
+        let inside = [106.0, 107.0, 103.0, 104.0];
+        assert_found(&[LONG_BULL, inside], BearishHarami, Bias::Bearish);
+        assert_not_found(&[[100.0, 115.0, 95.0, 110.0], inside], BearishHarami);
+    }
+
+    #[test]
+    fn piercing_line() {
+        assert_found(
+            &[LONG_BEAR, [98.0, 108.5, 97.5, 108.0]],
+            PiercingLine,
+            Bias::Bullish,
+        );
+        assert_not_found(&[LONG_BEAR, [98.0, 104.5, 97.5, 104.0]], PiercingLine);
+    }
+
+    #[test]
+    fn dark_cloud_cover() {
+        assert_found(
+            &[LONG_BULL, [112.0, 112.5, 101.5, 102.0]],
+            DarkCloudCover,
+            Bias::Bearish,
+        );
+        assert_not_found(&[LONG_BULL, [112.0, 112.5, 105.5, 106.0]], DarkCloudCover);
+    }
+
+    #[test]
+    fn morning_star() {
+        let star = [99.0, 100.0, 97.0, 98.5];
+        assert_found(
+            &[LONG_BEAR, star, [99.0, 107.5, 98.5, 107.0]],
+            MorningStar,
+            Bias::Bullish,
+        );
+        assert_not_found(&[LONG_BEAR, star, [99.0, 104.5, 98.5, 104.0]], MorningStar);
+    }
+
+    #[test]
+    fn evening_star() {
+        let star = [111.0, 113.0, 110.5, 111.5];
+        assert_found(
+            &[LONG_BULL, star, [111.0, 111.5, 102.5, 103.0]],
+            EveningStar,
+            Bias::Bearish,
+        );
+        assert_not_found(
+            &[LONG_BULL, star, [111.0, 111.5, 105.5, 106.0]],
+            EveningStar,
+        );
+    }
+
+    #[test]
+    fn three_white_soldiers() {
+        let first = [100.0, 104.2, 99.8, 104.0];
+        let second = [102.0, 106.2, 101.8, 106.0];
+        assert_found(
+            &[first, second, [104.0, 108.2, 103.8, 108.0]],
+            ThreeWhiteSoldiers,
+            Bias::Bullish,
+        );
+        // The last candle opens above the previous body.
+        assert_not_found(
+            &[first, second, [106.5, 110.2, 106.3, 110.0]],
+            ThreeWhiteSoldiers,
+        );
+    }
+
+    #[test]
+    fn three_black_crows() {
+        let first = [108.0, 108.2, 103.8, 104.0];
+        assert_found(
+            &[
+                first,
+                [106.0, 106.2, 101.8, 102.0],
+                [104.0, 104.2, 99.8, 100.0],
+            ],
+            ThreeBlackCrows,
+            Bias::Bearish,
+        );
+        // The second candle opens below the first body.
+        assert_not_found(
+            &[
+                first,
+                [103.5, 103.7, 99.3, 99.5],
+                [101.5, 101.7, 97.3, 97.5],
+            ],
+            ThreeBlackCrows,
+        );
+    }
+
+    #[test]
+    fn strength_is_clamped() {
+        let config = CandlestickConfig::default();
+        let matches = found_with(&after(&DOWN, &[HAMMER]), &config);
+        let hammer = matches.iter().find(|m| m.pattern == Hammer).unwrap();
+        assert_near(hammer.strength, 4.0 / 5.2, 1e-12);
+
+        // Engulfing strength is body / previous body / 2, capped at 1.
+        let matches = found_with(
+            &[[100.5, 101.0, 99.5, 100.0], [99.5, 103.5, 99.0, 103.0]],
+            &config,
+        );
+        let engulfing = matches
+            .iter()
+            .find(|m| m.pattern == BullishEngulfing)
+            .unwrap();
+        assert_eq!(engulfing.strength, 1.0);
+    }
+
+    #[test]
+    fn non_finite_strength_counts_as_zero() {
+        let config = CandlestickConfig {
+            doji_body: 0.0,
+            ..CandlestickConfig::default()
+        };
+        let matches = found_with(&[[100.0, 101.0, 99.0, 100.0]], &config);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].pattern, Doji);
+        assert_eq!(matches[0].strength, 0.0);
+    }
+
+    #[test]
+    fn decimal_matches_f64() {
+        let rows: Vec<[f64; 5]> = after(&DOWN, &[HAMMER])
+            .iter()
+            .map(|&[o, h, l, c]| [o, h, l, c, 1.0])
+            .collect();
+        let config = CandlestickConfig::default();
+        assert_eq!(
+            scan(&decimal_candles(&rows), &config),
+            scan(&candles(&rows), &config)
+        );
+    }
+}
@@ -0,0 +1,770 @@
+//! Classical chart patterns found in the sequence of swing highs and lows:
+//! double tops/bottoms, head and shoulders, triangles, wedges and flags.
+//!
+//! Each match lists its vertices with timestamps so the pattern can be drawn,
+//! the breakout level projected to the latest candle, and the first candle
+//! that closed beyond it, if any.
+
+use exchange_outpost_abi::Candle;
+use serde::{Deserialize, Serialize};
+
+use super::pivots::{self, Pivot, PivotKind};
+use super::{Bias, Line};
+use crate::indicators::Number;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChartPattern {
+    DoubleTop,
+    DoubleBottom,
+    HeadAndShoulders,
+    InverseHeadAndShoulders,
+    AscendingTriangle,
+    DescendingTriangle,
+    SymmetricalTriangle,
+    RisingWedge,
+    FallingWedge,
+    BullFlag,
+    BearFlag,
+}
+
+/// A named vertex of a pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct KeyPoint {
+    pub name: &'static str,
+    pub index: usize,
+    pub timestamp: i64,
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChartPatternMatch {
+    pub pattern: ChartPattern,
+    /// Expected breakout direction. Symmetrical triangles are neutral until
+    /// they break out, then take the breakout's direction.
+    pub direction: Bias,
+    pub points: Vec<KeyPoint>,
+    /// Level a close must cross to confirm the pattern, at the latest candle.
+    pub breakout_level: f64,
+    /// First candle after the pattern that closed beyond the breakout level.
+    pub breakout_index: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChartConfig {
+    /// Bars on each side a swing high/low must exceed.
+    pub swing_depth: usize,
+    /// Relative difference under which two prices count as equal.
+    pub tolerance: f64,
+    /// Smallest relative retracement between the peaks of a double top/bottom.
+    pub min_retracement: f64,
+    /// Relative slope per bar under which a line counts as flat.
+    pub flat_slope: f64,
+    /// Smallest relative move of a flag pole.
+    pub min_pole: f64,
+    /// Largest fraction of the pole a flag may retrace.
+    pub max_flag_retracement: f64,
+}
+
+impl Default for ChartConfig {
+    fn default() -> Self {
+        Self {
+            swing_depth: 3,
+            tolerance: 0.02,
+            min_retracement: 0.03,
+            flat_slope: 0.0005,
+            min_pole: 0.05,
+            max_flag_retracement: 0.5,
+        }
+    }
+}
+
+/// Kind of a [`SwingPoint`].
+pub type SwingKind = PivotKind;
+
+/// A confirmed swing high or low, priced in `f64`.
+pub type SwingPoint = Pivot<f64>;
+
+/// Fractal swing points: a high above the `depth` bars before it and not
+/// below the `depth` bars after it (lows mirrored). Only confirmed
+/// [`pivots::fractal`] pivots are kept, and consecutive swings of the same
+/// kind are merged into the more extreme one, so the result alternates.
+pub fn swing_points<T: Number>(candles: &[Candle<T>], depth: usize) -> Vec<SwingPoint> {
+    let confirmed: Vec<SwingPoint> = pivots::fractal(candles, depth, depth)
+        .into_iter()
+        .filter(|p| p.confirmed)
+        .map(|p| Pivot {
+            kind: p.kind,
+            index: p.index,
+            timestamp: p.timestamp,
+            price: p.price.to_f64(),
+            confirmed: p.confirmed,
+        })
+        .collect();
+    pivots::alternating(&confirmed)
+}
+
+fn key(name: &'static str, pivot: &SwingPoint) -> KeyPoint {
+    KeyPoint {
+        name,
+        index: pivot.index,
+        timestamp: pivot.timestamp,
+        price: pivot.price,
+    }
+}
+
+fn relative_diff(a: f64, b: f64) -> f64 {
+    let scale = (a.abs() + b.abs()) / 2.0;
+    if scale == 0.0 {
+        0.0
+    } else {
+        (a - b).abs() / scale
+    }
+}
+
+/// Finds every pattern in the swing sequence of `candles`.
+pub fn scan<T: Number>(candles: &[Candle<T>], config: &ChartConfig) -> Vec<ChartPatternMatch> {
+    let swings = swing_points(candles, config.swing_depth);
+    let closes: Vec<f64> = candles.iter().map(|c| c.close.to_f64()).collect();
+    let Some(last_index) = closes.len().checked_sub(1) else {
+        return Vec::new();
+    };
+    let scanner = Scanner {
+        swings: &swings,
+        closes: &closes,
+        last_index,
+        config,
+    };
+    let mut matches = Vec::new();
+    for i in 0..swings.len() {
+        if let Some(m) = scanner.double(i) {
+            matches.push(m);
+        }
+        if let Some(m) = scanner.head_and_shoulders(i) {
+            matches.push(m);
+        }
+        if let Some(m) = scanner.converging(i) {
+            matches.push(m);
+        }
+        if let Some(m) = scanner.flag(i) {
+            matches.push(m);
+        }
+    }
+    matches
+}
+
+/// Two highs and two lows with the lines through them; slopes are relative
+/// to the average swing price.
+#[derive(Clone, Copy)]
+struct Channel<'a> {
+    highs: [&'a SwingPoint; 2],
+    lows: [&'a SwingPoint; 2],
+    upper: Line,
+    lower: Line,
+    upper_slope: f64,
+    lower_slope: f64,
+}
+
+impl Channel<'_> {
+    fn points(&self) -> Vec<KeyPoint> {
+        let mut points = vec![
+            key("upper_1", self.highs[0]),
+            key("upper_2", self.highs[1]),
+            key("lower_1", self.lows[0]),
+            key("lower_2", self.lows[1]),
+        ];
+        points.sort_by_key(|p| p.index);
+        points
+    }
+}
+
+struct Scanner<'a> {
+    swings: &'a [SwingPoint],
+    closes: &'a [f64],
+    last_index: usize,
+    config: &'a ChartConfig,
+}
+
+impl Scanner<'_> {
+    /// Builds a match whose breakout is a close beyond `boundary` (above for
+    /// bullish, below for bearish) after the last point.
+    fn finish(
+        &self,
+        pattern: ChartPattern,
+        direction: Bias,
+        points: Vec<KeyPoint>,
+        boundary: Line,
+    ) -> ChartPatternMatch {
+        let after = points.iter().map(|p| p.index).max().unwrap_or(0) + 1;
+        let breakout_index = (after..self.closes.len()).find(|&i| {
+            let level = boundary.value_at(i);
+            match direction {
+                Bias::Bullish => self.closes[i] > level,
+                Bias::Bearish => self.closes[i] < level,
+                Bias::Neutral => false,
+            }
+        });
+        ChartPatternMatch {
+            pattern,
+            direction,
+            points,
+            breakout_level: boundary.value_at(self.last_index),
+            breakout_index,
+        }
+    }
+
+    fn double(&self, i: usize) -> Option<ChartPatternMatch> {
+        let [first, middle, second] = self.swings.get(i..i + 3)? else {
+            return None;
+        };
+        if relative_diff(first.price, second.price) > self.config.tolerance {
+            return None;
+        }
+        let peak = (first.price + second.price) / 2.0;
+        if relative_diff(peak, middle.price) < self.config.min_retracement {
+            return None;
+        }
+        let neckline = Line::through((middle.index, middle.price), (second.index, middle.price));
+        let (pattern, direction, names) = match first.kind {
+            PivotKind::High => (
+                ChartPattern::DoubleTop,
+                Bias::Bearish,
+                ["first_top", "trough", "second_top"],
+            ),
+            PivotKind::Low => (
+                ChartPattern::DoubleBottom,
+                Bias::Bullish,
+                ["first_bottom", "peak", "second_bottom"],
+            ),
+        };
+        let points = vec![
+            key(names[0], first),
+            key(names[1], middle),
+            key(names[2], second),
+        ];
+        Some(self.finish(pattern, direction, points, neckline))
+    }
+
+    fn head_and_shoulders(&self, i: usize) -> Option<ChartPatternMatch> {
+        let [left, left_neck, head, right_neck, right] = self.swings.get(i..i + 5)? else {
+            return None;
+        };
+        let tolerance = self.config.tolerance;
+        let head_beyond = |shoulder: &SwingPoint| match head.kind {
+            PivotKind::High => head.price > shoulder.price,
+            PivotKind::Low => head.price < shoulder.price,
+        };
+        if !head_beyond(left)
+            || !head_beyond(right)
+            || relative_diff(head.price, left.price) <= tolerance
+            || relative_diff(head.price, right.price) <= tolerance
+            || relative_diff(left.price, right.price) > tolerance
+        {
+            return None;
+        }
+        let neckline = Line::through(
+            (left_neck.index, left_neck.price),
+            (right_neck.index, right_neck.price),
+        );
+        let (pattern, direction) = match head.kind {
+            PivotKind::High => (ChartPattern::HeadAndShoulders, Bias::Bearish),
+            PivotKind::Low => (ChartPattern::InverseHeadAndShoulders, Bias::Bullish),
+        };
+        let points = vec![
+            key("left_shoulder", left),
+            key("left_neck", left_neck),
+            key("head", head),
+            key("right_neck", right_neck),
+            key("right_shoulder", right),
+        ];
+        Some(self.finish(pattern, direction, points, neckline))
+    }
+
+    /// Upper and lower lines through the two highs and two lows of the four
+    /// swings starting at `i`.
+    fn channel(&self, i: usize) -> Option<Channel<'_>> {
+        let window = self.swings.get(i..i + 4)?;
+        let (highs, lows): (Vec<&SwingPoint>, Vec<&SwingPoint>) =
+            window.iter().partition(|s| s.kind == PivotKind::High);
+        let (&[h1, h2], &[l1, l2]) = (&highs[..], &lows[..]) else {
+            return None;
+        };
+        let scale = window.iter().map(|s| s.price).sum::<f64>() / 4.0;
+        if scale <= 0.0 {
+            return None;
+        }
+        let upper = Line::through((h1.index, h1.price), (h2.index, h2.price));
+        let lower = Line::through((l1.index, l1.price), (l2.index, l2.price));
+        Some(Channel {
+            highs: [h1, h2],
+            lows: [l1, l2],
+            upper,
+            lower,
+            upper_slope: upper.slope / scale,
+            lower_slope: lower.slope / scale,
+        })
+    }
+
+    /// Triangles and wedges: converging upper and lower lines.
+    fn converging(&self, i: usize) -> Option<ChartPatternMatch> {
+        let channel = self.channel(i)?;
+        let Channel {
+            highs,
+            lows,
+            upper,
+            lower,
+            upper_slope: up,
+            lower_slope: low,
+        } = channel;
+        let start = highs[0].index.min(lows[0].index);
+        let end = highs[1].index.max(lows[1].index);
+        let converges = upper.value_at(end) - lower.value_at(end)
+            < upper.value_at(start) - lower.value_at(start);
+        if !converges {
+            return None;
+        }
+        let flat = self.config.flat_slope;
+        let is_flat = |slope: f64| slope.abs() <= flat;
+        let (pattern, direction) = if is_flat(up) && low > flat {
+            (ChartPattern::AscendingTriangle, Bias::Bullish)
+        } else if up < -flat && is_flat(low) {
+            (ChartPattern::DescendingTriangle, Bias::Bearish)
+        } else if up < -flat && low > flat {
+            (ChartPattern::SymmetricalTriangle, Bias::Neutral)
+        } else if up > flat && low > flat {
+            (ChartPattern::RisingWedge, Bias::Bearish)
+        } else if up < -flat && low < -flat {
+            (ChartPattern::FallingWedge, Bias::Bullish)
+        } else {
+            return None;
+        };
+        let points = channel.points();
+        Some(match direction {
+            Bias::Bullish => self.finish(pattern, direction, points, upper),
+            Bias::Bearish => self.finish(pattern, direction, points, lower),
+            Bias::Neutral => {
+                let up_break = self.finish(pattern, Bias::Bullish, points.clone(), upper);
+                let down_break = self.finish(pattern, Bias::Bearish, points, lower);
+                match (up_break.breakout_index, down_break.breakout_index) {
+                    (Some(u), Some(d)) if d < u => down_break,
+                    (Some(_), _) => up_break,
+                    (None, Some(_)) => down_break,
+                    (None, None) => ChartPatternMatch {
+                        direction: Bias::Neutral,
+                        ..up_break
+                    },
+                }
+            }
+        })
+    }
+
+    /// Flags: a strong pole into a short parallel channel sloping against it.
+    fn flag(&self, i: usize) -> Option<ChartPatternMatch> {
+        let pole_start = self.swings.get(i.checked_sub(1)?)?;
+        let channel = self.channel(i)?;
+        let Channel {
+            highs,
+            lows,
+            upper,
+            lower,
+            upper_slope: up,
+            lower_slope: low,
+        } = channel;
+        let flat = self.config.flat_slope;
+        if (up - low).abs() > flat {
+            return None;
+        }
+        let (pattern, direction, pole_end, boundary) = match pole_start.kind {
+            PivotKind::Low if up <= flat => {
+                (ChartPattern::BullFlag, Bias::Bullish, highs[0], upper)
+            }
+            PivotKind::High if low >= -flat => {
+                (ChartPattern::BearFlag, Bias::Bearish, lows[0], lower)
+            }
+            _ => return None,
+        };
+        if pole_end.index > lows[0].index.min(highs[0].index) {
+            return None;
+        }
+        let pole = (pole_end.price - pole_start.price).abs();
+        if relative_diff(pole_end.price, pole_start.price) < self.config.min_pole {
+            return None;
+        }
+        let retracement = match direction {
+            Bias::Bullish => pole_end.price - lows[0].price.min(lows[1].price),
+            _ => highs[0].price.max(highs[1].price) - pole_end.price,
+        };
+        if retracement > self.config.max_flag_retracement * pole {
+            return None;
+        }
+        let mut points = vec![key("pole_start", pole_start)];
+        points.extend(channel.points());
+        Some(self.finish(pattern, direction, points, boundary))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_near, decimal_candles, flat_candles};
+
+    const CONFIG: ChartConfig = ChartConfig {
+        swing_depth: 1,
+        tolerance: 0.02,
+        min_retracement: 0.03,
+        flat_slope: 0.0005,
+        min_pole: 0.05,
+        max_flag_retracement: 0.5,
+    };
+
+    /// Prices interpolated linearly between `(index, price)` vertices.
+    fn path(vertices: &[(usize, f64)]) -> Vec<f64> {
+        let mut prices = vec![vertices[0].1];
+        for pair in vertices.windows(2) {
+            let ((from, a), (to, b)) = (pair[0], pair[1]);
+            let step = (b - a) / (to - from) as f64;
+            prices.extend((1..=to - from).map(|k| a + step * k as f64));
+        }
+        prices
+    }
+
+    fn find(vertices: &[(usize, f64)], pattern: ChartPattern) -> Option<ChartPatternMatch> {
+        scan(&flat_candles(&path(vertices)), &CONFIG)
+            .into_iter()
+            .find(|m| m.pattern == pattern)
+    }
+
+    fn points(m: &ChartPatternMatch) -> Vec<(&'static str, usize, f64)> {
+        m.points
+            .iter()
+            .map(|p| (p.name, p.index, p.price))
+            .collect()
+    }
+
+    #[test]
+    fn swing_points_alternate_and_skip_unconfirmed_pivots() {
+        let candles = flat_candles(&[
+            10.0, 11.0, 13.0, 12.0, 12.5, 14.0, 12.0, 11.0, 10.0, 11.0, 12.0,
+        ]);
+        let swings: Vec<(SwingKind, usize, f64)> = swing_points(&candles, 2)
+            .iter()
+            .map(|s| (s.kind, s.index, s.price))
+            .collect();
+        // No low between the highs at 2 and 5, so the lower one is dropped;
+        // the last two bars are not confirmed.
+        assert_eq!(
+            swings,
+            vec![(SwingKind::High, 5, 14.0), (SwingKind::Low, 8, 10.0),]
+        );
+    }
+
+    #[test]
+    fn double_top() {
+        let vertices = [
+            (0, 90.0),
+            (5, 100.0),
+            (10, 92.0),
+            (15, 100.5),
+            (20, 88.0),
+            (22, 89.0),
+        ];
+        let m = find(&vertices, ChartPattern::DoubleTop).unwrap();
+        assert_eq!(m.direction, Bias::Bearish);
+        assert_eq!(
+            points(&m),
+            vec![
+                ("first_top", 5, 100.0),
+                ("trough", 10, 92.0),
+                ("second_top", 15, 100.5),
+            ]
+        );
+        assert_eq!(m.breakout_level, 92.0);
+        assert_eq!(m.breakout_index, Some(19));
+
+        let uneven = [
+            (0, 90.0),
+            (5, 100.0),
+            (10, 92.0),
+            (15, 104.0),
+            (20, 88.0),
+            (22, 89.0),
+        ];
+        assert_eq!(find(&uneven, ChartPattern::DoubleTop), None);
+    }
+
+    #[test]
+    fn double_bottom() {
+        let vertices = [
+            (0, 110.0),
+            (5, 100.0),
+            (10, 108.0),
+            (15, 99.5),
+            (20, 112.0),
+            (22, 111.0),
+        ];
+        let m = find(&vertices, ChartPattern::DoubleBottom).unwrap();
+        assert_eq!(m.direction, Bias::Bullish);
+        assert_eq!(m.breakout_level, 108.0);
+        assert_eq!(m.breakout_index, Some(19));
+
+        // The peak between the bottoms is less than 3% above them.
+        let shallow = [
+            (0, 110.0),
+            (5, 100.0),
+            (10, 102.0),
+            (15, 99.5),
+            (20, 112.0),
+            (22, 111.0),
+        ];
+        assert_eq!(find(&shallow, ChartPattern::DoubleBottom), None);
+    }
+
+    #[test]
+    fn head_and_shoulders() {
+        let vertices = [
+            (0, 90.0),
+            (4, 100.0),
+            (8, 95.0),
+            (12, 106.0),
+            (16, 95.5),
+            (20, 100.5),
+            (24, 88.0),
+            (26, 89.0),
+        ];
+        let m = find(&vertices, ChartPattern::HeadAndShoulders).unwrap();
+        assert_eq!(m.direction, Bias::Bearish);
+        assert_eq!(
+            points(&m),
+            vec![
+                ("left_shoulder", 4, 100.0),
+                ("left_neck", 8, 95.0),
+                ("head", 12, 106.0),
+                ("right_neck", 16, 95.5),
+                ("right_shoulder", 20, 100.5),
+            ]
+        );
+        // Sloping neckline projected to the last candle.
+        assert_near(m.breakout_level, 96.125, 1e-9);
+        assert_eq!(m.breakout_index, Some(22));
+
+        let mut lopsided = vertices;
+        lopsided[5] = (20, 104.0);
+        assert_eq!(find(&lopsided, ChartPattern::HeadAndShoulders), None);
+    }
+
+    #[test]
+    fn inverse_head_and_shoulders() {
+        let vertices = [
+            (0, 110.0),
+            (4, 100.0),
+            (8, 105.0),
+            (12, 94.0),
+            (16, 104.5),
+            (20, 99.5),
+            (24, 112.0),
+            (26, 111.0),
+        ];
+        let m = find(&vertices, ChartPattern::InverseHeadAndShoulders).unwrap();
+        assert_eq!(m.direction, Bias::Bullish);
+        assert_near(m.breakout_level, 103.875, 1e-9);
+        assert_eq!(m.breakout_index, Some(22));
+
+        // The head is not beyond the shoulders.
+        let mut flat_head = vertices;
+        flat_head[3] = (12, 99.8);
+        assert_eq!(
+            find(&flat_head, ChartPattern::InverseHeadAndShoulders),
+            None
+        );
+    }
+
+    #[test]
+    fn ascending_and_descending_triangles() {
+        let ascending = [
+            (0, 100.0),
+            (4, 110.0),
+            (8, 102.0),
+            (12, 110.0),
+            (16, 106.0),
+            (20, 113.0),
+            (22, 112.0),
+        ];
+        let m = find(&ascending, ChartPattern::AscendingTriangle).unwrap();
+        assert_eq!(m.direction, Bias::Bullish);
+        assert_eq!(
+            points(&m),
+            vec![
+                ("upper_1", 4, 110.0),
+                ("lower_1", 8, 102.0),
+                ("upper_2", 12, 110.0),
+                ("lower_2", 16, 106.0),
+            ]
+        );
+        assert_eq!(m.breakout_level, 110.0);
+        assert_eq!(m.breakout_index, Some(19));
+
+        // Falling lows: the lines diverge.
+        let diverging = [
+            (0, 100.0),
+            (4, 110.0),
+            (8, 102.0),
+            (12, 110.0),
+            (16, 98.0),
+            (20, 113.0),
+            (22, 112.0),
+        ];
+        assert_eq!(find(&diverging, ChartPattern::AscendingTriangle), None);
+
+        let descending = [
+            (0, 100.0),
+            (4, 90.0),
+            (8, 98.0),
+            (12, 90.0),
+            (16, 94.0),
+            (20, 87.0),
+            (22, 88.0),
+        ];
+        let m = find(&descending, ChartPattern::DescendingTriangle).unwrap();
+        assert_eq!(m.direction, Bias::Bearish);
+        assert_eq!(m.breakout_level, 90.0);
+        assert_eq!(m.breakout_index, Some(19));
+        assert_eq!(find(&ascending, ChartPattern::DescendingTriangle), None);
+    }
+
+    #[test]
+    fn symmetrical_triangle_takes_the_breakout_direction() {
+        let vertices = [
+            (0, 95.0),
+            (4, 110.0),
+            (8, 100.0),
+            (12, 107.0),
+            (16, 103.0),
+            (20, 112.0),
+            (22, 111.0),
+        ];
+        let m = find(&vertices, ChartPattern::SymmetricalTriangle).unwrap();
+        assert_eq!(m.direction, Bias::Bullish);
+        assert_eq!(m.breakout_index, Some(17));
+        // Upper line 110 - 0.375 per bar from bar 4, at bar 22.
+        assert_near(m.breakout_level, 103.25, 1e-9);
+
+        let inside = [
+            (0, 95.0),
+            (4, 110.0),
+            (8, 100.0),
+            (12, 107.0),
+            (16, 103.0),
+            (18, 104.5),
+        ];
+        let m = find(&inside, ChartPattern::SymmetricalTriangle).unwrap();
+        assert_eq!(m.direction, Bias::Neutral);
+        assert_eq!(m.breakout_index, None);
+
+        assert_eq!(find(&inside, ChartPattern::AscendingTriangle), None);
+    }
+
+    #[test]
+    fn rising_and_falling_wedges() {
+        let rising = [
+            (0, 98.0),
+            (4, 105.0),
+            (8, 100.0),
+            (12, 109.0),
+            (16, 106.0),
+            (18, 108.0),
+            (22, 100.0),
+        ];
+        let m = find(&rising, ChartPattern::RisingWedge).unwrap();
+        assert_eq!(m.direction, Bias::Bearish);
+        assert_eq!(m.breakout_index, Some(19));
+        // Lower line 100 + 0.75 per bar from bar 8, at bar 22.
+        assert_near(m.breakout_level, 110.5, 1e-9);
+        assert_eq!(find(&rising, ChartPattern::FallingWedge), None);
+
+        let falling = [
+            (0, 102.0),
+            (4, 95.0),
+            (8, 100.0),
+            (12, 91.0),
+            (16, 94.0),
+            (18, 92.0),
+            (22, 100.0),
+        ];
+        let m = find(&falling, ChartPattern::FallingWedge).unwrap();
+        assert_eq!(m.direction, Bias::Bullish);
+        assert_eq!(m.breakout_index, Some(19));
+        assert_eq!(find(&falling, ChartPattern::RisingWedge), None);
+    }
+
+    #[test]
+    fn bull_flag() {
+        let vertices = [
+            (0, 104.0),
+            (2, 100.0),
+            (6, 120.0),
+            (8, 116.0),
+            (10, 118.0),
+            (12, 114.0),
+            (16, 122.0),
+            (18, 121.0),
+        ];
+        let m = find(&vertices, ChartPattern::BullFlag).unwrap();
+        assert_eq!(m.direction, Bias::Bullish);
+        assert_eq!(m.points[0].name, "pole_start");
+        assert_eq!(m.points[0].index, 2);
+        assert_eq!(m.breakout_level, 114.0);
+        assert_eq!(m.breakout_index, Some(14));
+
+        // A 3% pole is too short.
+        let mut short_pole = vertices;
+        short_pole[0] = (0, 118.0);
+        short_pole[1] = (2, 116.5);
+        assert_eq!(find(&short_pole, ChartPattern::BullFlag), None);
+    }
+
+    #[test]
+    fn bear_flag() {
+        let vertices = [
+            (0, 136.0),
+            (2, 140.0),
+            (6, 120.0),
+            (8, 124.0),
+            (10, 122.0),
+            (12, 126.0),
+            (16, 118.0),
+            (18, 119.0),
+        ];
+        let m = find(&vertices, ChartPattern::BearFlag).unwrap();
+        assert_eq!(m.direction, Bias::Bearish);
+        assert_eq!(m.breakout_level, 126.0);
+        assert_eq!(m.breakout_index, Some(14));
+        assert_eq!(find(&vertices, ChartPattern::BullFlag), None);
+
+        // The flag retraces more than half the pole.
+        let mut deep = vertices;
+        deep[3] = (8, 129.0);
+        deep[5] = (12, 131.0);
+        assert_eq!(find(&deep, ChartPattern::BearFlag), None);
+    }
+
+    #[test]
+    fn decimal_matches_f64() {
+        let rows: Vec<[f64; 5]> = path(&[
+            (0, 90.0),
+            (5, 100.0),
+            (10, 92.0),
+            (15, 100.5),
+            (20, 88.0),
+            (22, 89.0),
+        ])
+        .into_iter()
+        .map(|p| [p, p, p, p, 1.0])
+        .collect();
+        let decimal = scan(&decimal_candles(&rows), &CONFIG);
+        let float = scan(
+            &flat_candles(&rows.iter().map(|r| r[3]).collect::<Vec<_>>()),
+            &CONFIG,
+        );
+        assert_eq!(decimal, float);
+    }
+}
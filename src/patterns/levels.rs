@@ -0,0 +1,320 @@
+//! Horizontal support and resistance zones clustered from pivot prices.
+//!
+//! Pivot highs and lows within `zone_width` of each other form a zone. Each
+//! pivot counts as a touch, weighted by the volume of its candle, and zones
+//! are ranked by touches and volume relative to the other zones found.
+//!
+//! ```ignore
+//! let zones = levels::zones(&candles, &LevelConfig::default());
+//! for zone in levels::approaching(&zones, 0.5, 1.0) {
+//!     // price is within 1% of a zone with at least half the top strength
+//! }
+//! ```
+
+use std::cmp::Ordering;
+
+use exchange_outpost_abi::Candle;
+use serde::{Deserialize, Serialize};
+
+use super::pivots::{self, Pivot};
+use crate::indicators::Number;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZoneKind {
+    /// Below the latest close.
+    Support,
+    /// Above the latest close.
+    Resistance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Zone<T> {
+    pub kind: ZoneKind,
+    pub lower: T,
+    pub upper: T,
+    /// Volume-weighted average of the pivot prices in the zone.
+    pub center: T,
+    pub touches: usize,
+    /// Volume of the pivot candles in the zone.
+    pub volume: T,
+    /// 0-1, relative to the strongest zone by touches and volume.
+    pub strength: f64,
+    pub first_touch: i64,
+    pub last_touch: i64,
+    /// Distance from the latest close to the nearest edge, zero inside the zone.
+    pub distance: T,
+    /// `distance` as a percentage of the latest close.
+    pub distance_percent: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LevelConfig {
+    /// Candles on each side a pivot must exceed.
+    pub pivot_depth: usize,
+    /// Largest zone height as a fraction of its price.
+    pub zone_width: f64,
+    /// Zones with fewer pivots are dropped.
+    pub min_touches: usize,
+}
+
+impl Default for LevelConfig {
+    fn default() -> Self {
+        Self {
+            pivot_depth: 5,
+            zone_width: 0.005,
+            min_touches: 2,
+        }
+    }
+}
+
+/// Support and resistance zones of `candles`, strongest first.
+pub fn zones<T: Number>(candles: &[Candle<T>], config: &LevelConfig) -> Vec<Zone<T>> {
+    let Some(last) = candles.last() else {
+        return Vec::new();
+    };
+    let mut pivots: Vec<Pivot<T>> =
+        pivots::fractal(candles, config.pivot_depth, config.pivot_depth)
+            .into_iter()
+            .filter(|p| p.confirmed)
+            .collect();
+    pivots.sort_by(|a, b| a.price.partial_cmp(&b.price).unwrap_or(Ordering::Equal));
+
+    let width = T::from_f64(config.zone_width);
+    let mut clusters: Vec<Vec<Pivot<T>>> = Vec::new();
+    for pivot in pivots {
+        match clusters.last_mut() {
+            Some(cluster) if pivot.price - cluster[0].price <= cluster[0].price.abs() * width => {
+                cluster.push(pivot)
+            }
+            _ => clusters.push(vec![pivot]),
+        }
+    }
+
+    let mut zones: Vec<Zone<T>> = clusters
+        .into_iter()
+        .filter(|cluster| cluster.len() >= config.min_touches)
+        .map(|cluster| zone(candles, &cluster, last.close))
+        .collect();
+    let max_touches = zones.iter().map(|z| z.touches).max().unwrap_or(0) as f64;
+    let max_volume = zones.iter().map(|z| z.volume.to_f64()).fold(0.0, f64::max);
+    for zone in &mut zones {
+        let volume_share = if max_volume > 0.0 {
+            zone.volume.to_f64() / max_volume
+        } else {
+            0.0
+        };
+        zone.strength = (zone.touches as f64 / max_touches + volume_share) / 2.0;
+    }
+    zones.sort_by(|a, b| b.strength.total_cmp(&a.strength));
+    zones
+}
+
+fn zone<T: Number>(candles: &[Candle<T>], cluster: &[Pivot<T>], close: T) -> Zone<T> {
+    let lower = cluster[0].price;
+    let upper = cluster[cluster.len() - 1].price;
+    let volume = cluster
+        .iter()
+        .fold(T::zero(), |acc, p| acc + candles[p.index].volume);
+    let center = if volume > T::zero() {
+        cluster
+            .iter()
+            .fold(T::zero(), |acc, p| acc + p.price * candles[p.index].volume)
+            / volume
+    } else {
+        cluster.iter().fold(T::zero(), |acc, p| acc + p.price) / T::from_usize(cluster.len())
+    };
+    let distance = if close > upper {
+        close - upper
+    } else if close < lower {
+        lower - close
+    } else {
+        T::zero()
+    };
+    let close_f64 = close.to_f64();
+    Zone {
+        kind: if center < close {
+            ZoneKind::Support
+        } else {
+            ZoneKind::Resistance
+        },
+        lower,
+        upper,
+        center,
+        touches: cluster.len(),
+        volume,
+        strength: 0.0,
+        first_touch: cluster
+            .iter()
+            .map(|p| p.timestamp)
+            .min()
+            .unwrap_or_default(),
+        last_touch: cluster
+            .iter()
+            .map(|p| p.timestamp)
+            .max()
+            .unwrap_or_default(),
+        distance,
+        distance_percent: if close_f64 == 0.0 {
+            0.0
+        } else {
+            distance.to_f64() / close_f64.abs() * 100.0
+        },
+    }
+}
+
+/// Zones of at least `min_strength` within `max_distance_percent` of the
+/// latest close.
+pub fn approaching<T: Number>(
+    zones: &[Zone<T>],
+    min_strength: f64,
+    max_distance_percent: f64,
+) -> impl Iterator<Item = &Zone<T>> {
+    zones
+        .iter()
+        .filter(move |z| z.strength >= min_strength && z.distance_percent <= max_distance_percent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_near, candles, decimal_candles};
+
+    const CONFIG: LevelConfig = LevelConfig {
+        pivot_depth: 1,
+        zone_width: 0.01,
+        min_touches: 2,
+    };
+
+    /// Confirmed pivots: highs 100 (volume 2), 100.5 (4), 94 (1) and
+    /// 100.2 (2); lows 96 (1), 90 (1) and 90.4 (3).
+    const PRICES: [(f64, f64); 8] = [
+        (95.0, 1.0),
+        (100.0, 2.0),
+        (96.0, 1.0),
+        (100.5, 4.0),
+        (90.0, 1.0),
+        (94.0, 1.0),
+        (90.4, 3.0),
+        (100.2, 2.0),
+    ];
+
+    fn rows(last: &[(f64, f64)]) -> Vec<[f64; 5]> {
+        PRICES
+            .iter()
+            .chain(last)
+            .map(|&(p, v)| [p, p, p, p, v])
+            .collect()
+    }
+
+    fn zones_after(last: &[(f64, f64)]) -> Vec<Zone<f64>> {
+        zones(&candles(&rows(last)), &CONFIG)
+    }
+
+    #[test]
+    fn clusters_pivots_into_ranked_zones() {
+        let zones = zones_after(&[(97.0, 1.0)]);
+        // 94 and 96 are single touches.
+        assert_eq!(zones.len(), 2);
+
+        let resistance = &zones[0];
+        assert_eq!(resistance.kind, ZoneKind::Resistance);
+        assert_eq!((resistance.lower, resistance.upper), (100.0, 100.5));
+        assert_near(resistance.center, 100.3, 1e-9);
+        assert_eq!(resistance.touches, 3);
+        assert_eq!(resistance.volume, 8.0);
+        assert_eq!(resistance.strength, 1.0);
+        assert_eq!((resistance.first_touch, resistance.last_touch), (60, 420));
+        assert_near(resistance.distance, 3.0, 1e-9);
+        assert_near(resistance.distance_percent, 300.0 / 97.0, 1e-9);
+
+        let support = &zones[1];
+        assert_eq!(support.kind, ZoneKind::Support);
+        assert_eq!((support.lower, support.upper), (90.0, 90.4));
+        assert_near(support.center, 90.3, 1e-9);
+        assert_eq!(support.touches, 2);
+        // Two of three touches and half the volume of the strongest zone.
+        assert_near(support.strength, (2.0 / 3.0 + 0.5) / 2.0, 1e-9);
+        assert_eq!((support.first_touch, support.last_touch), (240, 360));
+        assert_near(support.distance, 6.6, 1e-9);
+        assert_near(support.distance_percent, 660.0 / 97.0, 1e-9);
+    }
+
+    #[test]
+    fn zone_width_is_measured_from_its_lowest_price() {
+        let config = LevelConfig {
+            zone_width: 0.004,
+            ..CONFIG
+        };
+        // 100.5 is within 0.4% of 100.2 but not of 100, and 90.4 is not
+        // within 0.4% of 90.
+        let zones = zones(&candles(&rows(&[(97.0, 1.0)])), &config);
+        assert_eq!(zones.len(), 1);
+        assert_eq!((zones[0].lower, zones[0].upper), (100.0, 100.2));
+        assert_eq!(zones[0].touches, 2);
+        assert_eq!(zones[0].strength, 1.0);
+    }
+
+    #[test]
+    fn close_inside_a_zone_is_classified_by_its_center() {
+        let zones = zones_after(&[(100.1, 1.0)]);
+        assert_eq!(zones[0].kind, ZoneKind::Resistance);
+        assert_eq!(zones[0].distance, 0.0);
+        assert_eq!(zones[0].distance_percent, 0.0);
+
+        // 99 is a single low just outside the 1% of 100.
+        let zones = zones_after(&[(99.0, 1.0), (100.45, 1.0)]);
+        assert_eq!(zones[0].kind, ZoneKind::Support);
+        assert_eq!(zones[0].distance, 0.0);
+    }
+
+    #[test]
+    fn without_volume_strength_counts_touches_and_center_is_the_mean() {
+        let rows: Vec<[f64; 5]> = rows(&[(97.0, 1.0)])
+            .into_iter()
+            .map(|[o, h, l, c, _]| [o, h, l, c, 0.0])
+            .collect();
+        let zones = zones(&candles(&rows), &CONFIG);
+        assert_near(zones[0].center, (100.0 + 100.2 + 100.5) / 3.0, 1e-9);
+        assert_eq!(zones[0].strength, 0.5);
+        assert_near(zones[1].center, 90.2, 1e-9);
+        assert_near(zones[1].strength, 1.0 / 3.0, 1e-9);
+    }
+
+    #[test]
+    fn approaching_filters_by_strength_and_distance() {
+        let zones = zones_after(&[(97.0, 1.0)]);
+        let near = |strength, distance| {
+            approaching(&zones, strength, distance)
+                .map(|z| z.kind)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(near(0.5, 5.0), vec![ZoneKind::Resistance]);
+        assert_eq!(
+            near(0.5, 10.0),
+            vec![ZoneKind::Resistance, ZoneKind::Support]
+        );
+        assert_eq!(near(0.9, 10.0), vec![ZoneKind::Resistance]);
+        assert_eq!(near(0.5, 3.0), Vec::new());
+    }
+
+    #[test]
+    fn empty_and_pivotless_series_have_no_zones() {
+        assert_eq!(zones::<f64>(&[], &CONFIG), Vec::new());
+        assert_eq!(zones(&candles(&rows(&[])[..2]), &CONFIG), Vec::new());
+    }
+
+    #[test]
+    fn decimal_matches_f64() {
+        let rows = rows(&[(97.0, 1.0)]);
+        let decimal = zones(&decimal_candles(&rows), &CONFIG);
+        let float = zones(&candles(&rows), &CONFIG);
+        assert_eq!(decimal.len(), float.len());
+        for (d, f) in decimal.iter().zip(&float) {
+            assert_eq!((d.kind, d.touches), (f.kind, f.touches));
+            assert_near(d.center.to_f64(), f.center, 1e-9);
+            assert_near(d.distance.to_f64(), f.distance, 1e-9);
+            assert_near(d.strength, f.strength, 1e-9);
+        }
+    }
+}
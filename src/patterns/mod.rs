@@ -0,0 +1,42 @@
+//! Price pattern recognition over `Candle` series.
+
+use serde::{Deserialize, Serialize};
+
+pub mod candlestick;
+pub mod chart;
+pub mod levels;
+pub mod pivots;
+pub mod trendlines;
+
+/// Market direction a pattern suggests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bias {
+    Bullish,
+    Bearish,
+    Neutral,
+}
+
+/// Straight line through price points, with `x` as the bar index.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Line {
+    /// Price change per bar.
+    pub slope: f64,
+    /// Price at bar index 0.
+    pub intercept: f64,
+}
+
+impl Line {
+    pub fn through(a: (usize, f64), b: (usize, f64)) -> Self {
+        let dx = b.0 as f64 - a.0 as f64;
+        let slope = if dx == 0.0 { 0.0 } else { (b.1 - a.1) / dx };
+        Self {
+            slope,
+            intercept: a.1 - slope * a.0 as f64,
+        }
+    }
+
+    pub fn value_at(&self, index: usize) -> f64 {
+        self.intercept + self.slope * index as f64
+    }
+}
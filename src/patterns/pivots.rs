@@ -0,0 +1,342 @@
+//! Swing high/low detection: fractal pivots and ZigZag.
+//!
+//! Pivots near the end of the series can still be invalidated by later
+//! candles; those are returned with `confirmed: false`.
+
+use exchange_outpost_abi::Candle;
+use serde::{Deserialize, Serialize};
+
+use crate::indicators::Number;
+use crate::indicators::volatility::atr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PivotKind {
+    High,
+    Low,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Pivot<T> {
+    pub kind: PivotKind,
+    pub index: usize,
+    pub timestamp: i64,
+    pub price: T,
+    /// Whether enough later candles exist that the pivot can no longer move.
+    pub confirmed: bool,
+}
+
+/// Minimum reversal for a ZigZag leg.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZigZagThreshold<T> {
+    /// Percentage of the pivot price.
+    Percent(T),
+    /// Multiple of the ATR at the reversing candle.
+    Atr { period: usize, multiplier: T },
+}
+
+fn pivot<T: Number>(
+    candles: &[Candle<T>],
+    kind: PivotKind,
+    index: usize,
+    confirmed: bool,
+) -> Pivot<T> {
+    Pivot {
+        kind,
+        index,
+        timestamp: candles[index].timestamp,
+        price: price(&candles[index], kind),
+        confirmed,
+    }
+}
+
+fn price<T: Number>(candle: &Candle<T>, kind: PivotKind) -> T {
+    match kind {
+        PivotKind::High => candle.high,
+        PivotKind::Low => candle.low,
+    }
+}
+
+/// First most extreme price of `kind` in `candles[from..=to]`: where a new
+/// leg starts after a reversal confirmed at `to`. Earlier candles can be more
+/// extreme than the reversing one when the ATR was still warming up or larger.
+fn leg_start<T: Number>(
+    candles: &[Candle<T>],
+    kind: PivotKind,
+    from: usize,
+    to: usize,
+) -> (usize, T) {
+    let mut start = (from, price(&candles[from], kind));
+    for (i, candle) in candles.iter().enumerate().take(to + 1).skip(from + 1) {
+        let value = price(candle, kind);
+        let beyond = match kind {
+            PivotKind::High => value > start.1,
+            PivotKind::Low => value < start.1,
+        };
+        if beyond {
+            start = (i, value);
+        }
+    }
+    start
+}
+
+/// Fractal pivots: a high above the `left` candles before it and not below
+/// the `right` candles after it, lows mirrored. Candles with fewer than
+/// `right` candles after them are tentative pivots if they hold against the
+/// candles that do exist. A candle can be both a high and a low pivot.
+pub fn fractal<T: Number>(candles: &[Candle<T>], left: usize, right: usize) -> Vec<Pivot<T>> {
+    let mut pivots = Vec::new();
+    for i in left..candles.len() {
+        let before = &candles[i - left..i];
+        let after = &candles[i + 1..(i + 1 + right).min(candles.len())];
+        let confirmed = after.len() == right;
+        let candle = &candles[i];
+        if before.iter().all(|c| candle.high > c.high)
+            && after.iter().all(|c| candle.high >= c.high)
+        {
+            pivots.push(pivot(candles, PivotKind::High, i, confirmed));
+        }
+        if before.iter().all(|c| candle.low < c.low) && after.iter().all(|c| candle.low <= c.low) {
+            pivots.push(pivot(candles, PivotKind::Low, i, confirmed));
+        }
+    }
+    pivots
+}
+
+/// ZigZag pivots: the extreme of each leg, confirmed once price reverses from
+/// it by at least `threshold`. The extreme of the last, still running leg is
+/// returned as a tentative pivot. Pivots alternate between highs and lows.
+pub fn zigzag<T: Number>(candles: &[Candle<T>], threshold: ZigZagThreshold<T>) -> Vec<Pivot<T>> {
+    let Some(first) = candles.first() else {
+        return Vec::new();
+    };
+    let ranges = match threshold {
+        ZigZagThreshold::Atr { period, .. } => atr(candles, period),
+        ZigZagThreshold::Percent(_) => Vec::new(),
+    };
+    let reversal = |index: usize, extreme: T| match threshold {
+        ZigZagThreshold::Percent(percent) => Some(extreme.abs() * percent / T::from_usize(100)),
+        ZigZagThreshold::Atr { multiplier, .. } => Some(ranges[index]? * multiplier),
+    };
+
+    let mut pivots = Vec::new();
+    // Kind of the extreme the current leg is heading towards; `None` until
+    // the first reversal decides the initial direction.
+    let mut leg: Option<PivotKind> = None;
+    let (mut high, mut low) = ((0, first.high), (0, first.low));
+    for (i, candle) in candles.iter().enumerate().skip(1) {
+        if leg != Some(PivotKind::Low) && candle.high > high.1 {
+            high = (i, candle.high);
+        }
+        if leg != Some(PivotKind::High) && candle.low < low.1 {
+            low = (i, candle.low);
+        }
+        if leg != Some(PivotKind::Low)
+            && high.0 < i
+            && let Some(min) = reversal(i, high.1)
+            && high.1 - candle.low >= min
+        {
+            pivots.push(pivot(candles, PivotKind::High, high.0, true));
+            leg = Some(PivotKind::Low);
+            low = leg_start(candles, PivotKind::Low, high.0 + 1, i);
+        } else if leg != Some(PivotKind::High)
+            && low.0 < i
+            && let Some(min) = reversal(i, low.1)
+            && candle.high - low.1 >= min
+        {
+            pivots.push(pivot(candles, PivotKind::Low, low.0, true));
+            leg = Some(PivotKind::High);
+            high = leg_start(candles, PivotKind::High, low.0 + 1, i);
+        }
+    }
+    match leg {
+        Some(PivotKind::High) => pivots.push(pivot(candles, PivotKind::High, high.0, false)),
+        Some(PivotKind::Low) => pivots.push(pivot(candles, PivotKind::Low, low.0, false)),
+        None => {}
+    }
+    pivots
+}
+
+/// Merges consecutive pivots of the same kind into the more extreme one, so
+/// highs and lows alternate.
+pub fn alternating<T: Number>(pivots: &[Pivot<T>]) -> Vec<Pivot<T>> {
+    let mut merged: Vec<Pivot<T>> = Vec::new();
+    for pivot in pivots {
+        match merged.last_mut() {
+            Some(last) if last.kind == pivot.kind => {
+                let more_extreme = match pivot.kind {
+                    PivotKind::High => pivot.price > last.price,
+                    PivotKind::Low => pivot.price < last.price,
+                };
+                if more_extreme {
+                    *last = *pivot;
+                }
+            }
+            _ => merged.push(*pivot),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::testing::{candles, decimal_candles, flat_candles};
+
+    fn summary<T: Copy>(pivots: &[Pivot<T>]) -> Vec<(PivotKind, usize, T, bool)> {
+        pivots
+            .iter()
+            .map(|p| (p.kind, p.index, p.price, p.confirmed))
+            .collect()
+    }
+
+    #[test]
+    fn fractal_confirms_pivots_with_enough_candles_after() {
+        let candles = flat_candles(&[1.0, 3.0, 2.0, 1.0, 2.0, 4.0, 3.0]);
+        assert_eq!(
+            summary(&fractal(&candles, 1, 2)),
+            vec![
+                (PivotKind::High, 1, 3.0, true),
+                (PivotKind::Low, 3, 1.0, true),
+                // Only one and zero candles after them.
+                (PivotKind::High, 5, 4.0, false),
+                (PivotKind::Low, 6, 3.0, false),
+            ]
+        );
+        assert_eq!(fractal(&candles[..1], 1, 1), Vec::new());
+    }
+
+    #[test]
+    fn fractal_outside_bar_is_both_high_and_low() {
+        let candles = candles(&[
+            [5.0, 6.0, 4.0, 5.0, 1.0],
+            [5.0, 8.0, 1.0, 5.0, 1.0],
+            [5.0, 6.0, 4.0, 5.0, 1.0],
+        ]);
+        assert_eq!(
+            summary(&fractal(&candles, 1, 1)),
+            vec![
+                (PivotKind::High, 1, 8.0, true),
+                (PivotKind::Low, 1, 1.0, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn fractal_takes_the_first_of_equal_highs() {
+        let candles = flat_candles(&[1.0, 3.0, 3.0, 2.0, 2.5]);
+        assert_eq!(
+            summary(&fractal(&candles, 1, 1)),
+            vec![
+                (PivotKind::High, 1, 3.0, true),
+                (PivotKind::Low, 3, 2.0, true),
+                (PivotKind::High, 4, 2.5, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn zigzag_percent_alternates_and_ends_tentative() {
+        let candles = flat_candles(&[100.0, 104.0, 110.0, 105.0, 99.0, 102.0, 108.0, 103.0, 112.0]);
+        assert_eq!(
+            summary(&zigzag(&candles, ZigZagThreshold::Percent(10.0))),
+            vec![
+                (PivotKind::Low, 0, 100.0, true),
+                (PivotKind::High, 2, 110.0, true),
+                // 108 is only 9.1% above 99.
+                (PivotKind::Low, 4, 99.0, true),
+                (PivotKind::High, 8, 112.0, false),
+            ]
+        );
+        assert_eq!(
+            zigzag(&candles[..3], ZigZagThreshold::Percent(20.0)),
+            Vec::new()
+        );
+        assert_eq!(zigzag(&[], ZigZagThreshold::Percent(10.0)), Vec::new());
+    }
+
+    #[test]
+    fn zigzag_atr_waits_for_the_atr() {
+        let candles = flat_candles(&[100.0, 90.0]);
+        let threshold = ZigZagThreshold::Atr {
+            period: 3,
+            multiplier: 1.0,
+        };
+        assert_eq!(zigzag(&candles, threshold), Vec::new());
+        assert_eq!(
+            summary(&zigzag(&candles, ZigZagThreshold::Percent(5.0))),
+            vec![
+                (PivotKind::High, 0, 100.0, true),
+                (PivotKind::Low, 1, 90.0, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn zigzag_atr_keeps_extremes_from_the_warm_up() {
+        // True ranges 0, 5, 1, 1, 4, 2, 3; the ATR(3) is first available at
+        // index 2, where the drop from 100 confirms the high.
+        let candles = flat_candles(&[100.0, 95.0, 96.0, 97.0, 101.0, 99.0, 96.0]);
+        let threshold = ZigZagThreshold::Atr {
+            period: 3,
+            multiplier: 1.0,
+        };
+        assert_eq!(
+            summary(&zigzag(&candles, threshold)),
+            vec![
+                (PivotKind::High, 0, 100.0, true),
+                // The low of the warm-up candle, not of the reversing one.
+                (PivotKind::Low, 1, 95.0, true),
+                // 101 - 99 = 2 is below the ATR of 2.30 at index 5.
+                (PivotKind::High, 4, 101.0, true),
+                (PivotKind::Low, 6, 96.0, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn zigzag_decimal_matches_f64() {
+        let prices = [100.0, 104.0, 110.0, 105.0, 99.0, 102.0, 108.0, 103.0, 112.0];
+        let rows: Vec<[f64; 5]> = prices.iter().map(|&p| [p, p, p, p, 1.0]).collect();
+        let decimal = zigzag(
+            &decimal_candles(&rows),
+            ZigZagThreshold::Percent(Decimal::from_usize(10)),
+        );
+        let float = zigzag(&candles(&rows), ZigZagThreshold::Percent(10.0));
+        assert_eq!(
+            decimal
+                .iter()
+                .map(|p| (p.kind, p.index, p.confirmed))
+                .collect::<Vec<_>>(),
+            float
+                .iter()
+                .map(|p| (p.kind, p.index, p.confirmed))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn alternating_keeps_the_more_extreme_of_each_run() {
+        let candles = flat_candles(&[10.0, 12.0, 5.0, 6.0, 11.0, 11.0]);
+        let pivots = [
+            pivot(&candles, PivotKind::High, 0, true),
+            pivot(&candles, PivotKind::High, 1, true),
+            pivot(&candles, PivotKind::Low, 2, true),
+            pivot(&candles, PivotKind::Low, 3, true),
+            pivot(&candles, PivotKind::High, 4, true),
+            pivot(&candles, PivotKind::High, 5, false),
+        ];
+        assert_eq!(
+            summary(&alternating(&pivots)),
+            vec![
+                (PivotKind::High, 1, 12.0, true),
+                (PivotKind::Low, 2, 5.0, true),
+                // Ties keep the earlier pivot.
+                (PivotKind::High, 4, 11.0, true),
+            ]
+        );
+    }
+}
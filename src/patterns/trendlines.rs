@@ -0,0 +1,284 @@
+//! Trendlines through swing points, with breakout detection.
+//!
+//! Support lines connect pivot lows and resistance lines pivot highs. A line
+//! is kept when at least `min_touches` pivots lie within `tolerance` of it and
+//! no close crosses it between its first and last touch. The first close
+//! beyond the line after its last touch is the breakout.
+
+use exchange_outpost_abi::Candle;
+use serde::{Deserialize, Serialize};
+
+use super::pivots::{self, Pivot, PivotKind};
+use crate::indicators::Number;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendlineKind {
+    Support,
+    Resistance,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Trendline<T> {
+    pub kind: TrendlineKind,
+    /// Price change per candle.
+    pub slope: T,
+    /// Pivots on the line, oldest first.
+    pub touches: Vec<Pivot<T>>,
+    /// Line value at the latest candle.
+    pub projected: T,
+    pub projected_timestamp: i64,
+    /// First candle after the last touch that closed beyond the line.
+    pub breakout_index: Option<usize>,
+}
+
+impl<T: Number> Trendline<T> {
+    /// Line value at candle `index`.
+    pub fn value_at(&self, index: usize) -> T {
+        line_value(&self.touches[0], self.slope, index)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrendlineConfig {
+    /// Candles on each side a pivot must exceed.
+    pub pivot_depth: usize,
+    /// Largest distance from the line, as a fraction of price, that still
+    /// counts as a touch. Closes must cross by more than this to break it.
+    pub tolerance: f64,
+    pub min_touches: usize,
+    /// Only the most recent pivots of each kind are connected.
+    pub max_pivots: usize,
+}
+
+impl Default for TrendlineConfig {
+    fn default() -> Self {
+        Self {
+            pivot_depth: 3,
+            tolerance: 0.003,
+            min_touches: 3,
+            max_pivots: 20,
+        }
+    }
+}
+
+fn line_value<T: Number>(anchor: &Pivot<T>, slope: T, index: usize) -> T {
+    if index >= anchor.index {
+        anchor.price + slope * T::from_usize(index - anchor.index)
+    } else {
+        anchor.price - slope * T::from_usize(anchor.index - index)
+    }
+}
+
+/// Support and resistance trendlines of `candles`, most touches first.
+/// Lines whose touches are all on a line already returned are skipped.
+pub fn trendlines<T: Number>(candles: &[Candle<T>], config: &TrendlineConfig) -> Vec<Trendline<T>> {
+    let pivots: Vec<Pivot<T>> = pivots::fractal(candles, config.pivot_depth, config.pivot_depth)
+        .into_iter()
+        .filter(|p| p.confirmed)
+        .collect();
+    let mut lines = fit(candles, &pivots, PivotKind::Low, config);
+    lines.extend(fit(candles, &pivots, PivotKind::High, config));
+    lines.sort_by(|a, b| {
+        b.touches.len().cmp(&a.touches.len()).then(
+            b.touches[b.touches.len() - 1]
+                .index
+                .cmp(&a.touches[a.touches.len() - 1].index),
+        )
+    });
+
+    let mut kept: Vec<Trendline<T>> = Vec::new();
+    for line in lines {
+        let covered = kept.iter().any(|k| {
+            k.kind == line.kind
+                && line
+                    .touches
+                    .iter()
+                    .all(|t| k.touches.iter().any(|kt| kt.index == t.index))
+        });
+        if !covered {
+            kept.push(line);
+        }
+    }
+    kept
+}
+
+fn fit<T: Number>(
+    candles: &[Candle<T>],
+    pivots: &[Pivot<T>],
+    kind: PivotKind,
+    config: &TrendlineConfig,
+) -> Vec<Trendline<T>> {
+    let Some(last) = candles.len().checked_sub(1) else {
+        return Vec::new();
+    };
+    let same: Vec<&Pivot<T>> = pivots.iter().filter(|p| p.kind == kind).collect();
+    let same = &same[same.len().saturating_sub(config.max_pivots)..];
+    let tolerance = T::from_f64(config.tolerance);
+    // Whether `close` is beyond the line value by more than the tolerance.
+    let crosses = |close: T, value: T| {
+        let margin = value.abs() * tolerance;
+        match kind {
+            PivotKind::Low => close < value - margin,
+            PivotKind::High => close > value + margin,
+        }
+    };
+
+    let mut lines = Vec::new();
+    for (a, first) in same.iter().enumerate() {
+        for second in &same[a + 1..] {
+            let slope = (second.price - first.price) / T::from_usize(second.index - first.index);
+            let touches: Vec<Pivot<T>> = same[a..]
+                .iter()
+                .filter(|p| {
+                    let value = line_value(first, slope, p.index);
+                    (p.price - value).abs() <= value.abs() * tolerance
+                })
+                .map(|p| **p)
+                .collect();
+            if touches.len() < config.min_touches {
+                continue;
+            }
+            let last_touch = touches[touches.len() - 1].index;
+            let holds = (first.index..=last_touch)
+                .all(|i| !crosses(candles[i].close, line_value(first, slope, i)));
+            if !holds {
+                continue;
+            }
+            let breakout_index = (last_touch + 1..candles.len())
+                .find(|&i| crosses(candles[i].close, line_value(first, slope, i)));
+            lines.push(Trendline {
+                kind: match kind {
+                    PivotKind::Low => TrendlineKind::Support,
+                    PivotKind::High => TrendlineKind::Resistance,
+                },
+                slope,
+                projected: line_value(first, slope, last),
+                projected_timestamp: candles[last].timestamp,
+                touches,
+                breakout_index,
+            });
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_near, decimal_candles, flat_candles};
+
+    const CONFIG: TrendlineConfig = TrendlineConfig {
+        pivot_depth: 1,
+        tolerance: 0.005,
+        min_touches: 3,
+        max_pivots: 20,
+    };
+
+    /// Lows at 2, 6, 10 and 14 and highs at 4, 8, 12 and 16 on two parallel
+    /// lines rising 0.5 per candle; the last two candles fall through both.
+    const PRICES: [f64; 19] = [
+        104.0, 102.0, 100.0, 103.0, 106.0, 104.0, 102.0, 105.0, 108.0, 106.0, 104.0, 107.0, 110.0,
+        108.0, 106.0, 109.0, 112.0, 106.0, 100.0,
+    ];
+
+    fn touches<T>(line: &Trendline<T>) -> Vec<usize> {
+        line.touches.iter().map(|t| t.index).collect()
+    }
+
+    fn support(lines: &[Trendline<f64>]) -> Vec<Vec<usize>> {
+        lines
+            .iter()
+            .filter(|l| l.kind == TrendlineKind::Support)
+            .map(touches)
+            .collect()
+    }
+
+    #[test]
+    fn fits_support_and_resistance_lines() {
+        let lines = trendlines(&flat_candles(&PRICES), &CONFIG);
+        // Every pair of pivots on a line fits it again; only one is kept.
+        assert_eq!(lines.len(), 2);
+
+        let resistance = &lines[0];
+        assert_eq!(resistance.kind, TrendlineKind::Resistance);
+        assert_eq!(touches(resistance), vec![4, 8, 12, 16]);
+        assert_near(resistance.slope, 0.5, 1e-9);
+        assert_near(resistance.projected, 113.0, 1e-9);
+        assert_eq!(resistance.projected_timestamp, 18 * 60);
+        assert_eq!(resistance.breakout_index, None);
+
+        let support = &lines[1];
+        assert_eq!(support.kind, TrendlineKind::Support);
+        assert_eq!(touches(support), vec![2, 6, 10, 14]);
+        assert_near(support.projected, 108.0, 1e-9);
+        assert_near(support.value_at(0), 99.0, 1e-9);
+        // 106 is more than 0.5% below the line at 107.5.
+        assert_eq!(support.breakout_index, Some(17));
+    }
+
+    #[test]
+    fn touches_are_within_tolerance_of_the_line() {
+        let mut prices = PRICES;
+        prices[10] = 104.4;
+        let candles = flat_candles(&prices);
+
+        let lines = trendlines(&candles, &CONFIG);
+        assert_eq!(support(&lines), vec![vec![2, 6, 10, 14]]);
+
+        // 0.4 off the line is outside 0.3% of 104, so the pivots at 10 and 14
+        // are on different lines through 2 and 6.
+        let config = TrendlineConfig {
+            tolerance: 0.003,
+            ..CONFIG
+        };
+        let lines = trendlines(&candles, &config);
+        assert_eq!(support(&lines), vec![vec![2, 6, 14], vec![2, 6, 10]]);
+    }
+
+    #[test]
+    fn rejects_lines_closed_through_between_touches() {
+        let mut prices = PRICES;
+        // A low at 8 closing 2 below the support line.
+        prices[8] = 101.0;
+        let lines = trendlines(&flat_candles(&prices), &CONFIG);
+        assert_eq!(support(&lines), Vec::<Vec<usize>>::new());
+        assert_eq!(lines.len(), 1);
+        assert_eq!(touches(&lines[0]), vec![4, 12, 16]);
+    }
+
+    #[test]
+    fn requires_min_touches() {
+        let config = TrendlineConfig {
+            min_touches: 5,
+            ..CONFIG
+        };
+        assert_eq!(trendlines(&flat_candles(&PRICES), &config), Vec::new());
+        assert_eq!(trendlines::<f64>(&[], &CONFIG), Vec::new());
+    }
+
+    #[test]
+    fn connects_only_the_most_recent_pivots() {
+        let config = TrendlineConfig {
+            max_pivots: 3,
+            ..CONFIG
+        };
+        let lines = trendlines(&flat_candles(&PRICES), &config);
+        assert_eq!(touches(&lines[0]), vec![8, 12, 16]);
+        assert_eq!(touches(&lines[1]), vec![6, 10, 14]);
+    }
+
+    #[test]
+    fn decimal_matches_f64() {
+        let rows: Vec<[f64; 5]> = PRICES.iter().map(|&p| [p, p, p, p, 1.0]).collect();
+        let decimal = trendlines(&decimal_candles(&rows), &CONFIG);
+        let float = trendlines(&flat_candles(&PRICES), &CONFIG);
+        assert_eq!(decimal.len(), float.len());
+        for (d, f) in decimal.iter().zip(&float) {
+            assert_eq!((d.kind, touches(d)), (f.kind, touches(f)));
+            assert_eq!(d.breakout_index, f.breakout_index);
+            assert_near(d.slope.to_f64(), f.slope, 1e-9);
+            assert_near(d.projected.to_f64(), f.projected, 1e-9);
+        }
+    }
+}
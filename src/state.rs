@@ -0,0 +1,253 @@
+//! Typed state persisted between invocations in Extism vars.
+//!
+//! Values are stored as JSON in a `{"version": n, "data": ...}` envelope.
+//! When the stored version is older than the current one, the registered
+//! migrations are applied in order before deserializing.
+//!
+//! ```ignore
+//! #[derive(Default, Serialize, Deserialize)]
+//! struct Memory {
+//!     last_alert: Option<i64>,
+//! }
+//!
+//! let state = State::<Memory>::new("memory", 2).with_migration(1, |mut v| {
+//!     v["last_alert"] = v["last_alert_ts"].take();
+//!     Ok(v)
+//! });
+//! let mut memory = state.load_or_default()?;
+//! memory.last_alert = Some(now);
+//! state.save(&memory)?;
+//! ```
+
+use std::marker::PhantomData;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use crate::error::FunctionError;
+use crate::host;
+
+/// Upgrades stored data from one version to the next.
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+pub struct State<T> {
+    key: String,
+    version: u32,
+    migrations: Vec<(u32, Migration)>,
+    _data: PhantomData<T>,
+}
+
+impl<T: Serialize + DeserializeOwned> State<T> {
+    pub fn new(key: &str, version: u32) -> Self {
+        Self {
+            key: key.to_string(),
+            version,
+            migrations: Vec::new(),
+            _data: PhantomData,
+        }
+    }
+
+    /// Registers the migration from `from_version` to `from_version + 1`.
+    pub fn with_migration(mut self, from_version: u32, migration: Migration) -> Self {
+        self.migrations.push((from_version, migration));
+        self
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn load(&self) -> Result<Option<T>, FunctionError> {
+        let Some(bytes) = host::var_get(&self.key)? else {
+            return Ok(None);
+        };
+        let envelope: Value =
+            serde_json::from_slice(&bytes).map_err(|e| FunctionError::state(&self.key, e))?;
+        let stored_version = envelope["version"]
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| FunctionError::state(&self.key, "missing version"))?;
+        let data = self.migrate(stored_version, envelope["data"].clone())?;
+        serde_json::from_value(data)
+            .map(Some)
+            .map_err(|e| FunctionError::state(&self.key, e))
+    }
+
+    pub fn load_or_default(&self) -> Result<T, FunctionError>
+    where
+        T: Default,
+    {
+        Ok(self.load()?.unwrap_or_default())
+    }
+
+    pub fn save(&self, data: &T) -> Result<(), FunctionError> {
+        let envelope = json!({ "version": self.version, "data": data });
+        let bytes =
+            serde_json::to_vec(&envelope).map_err(|e| FunctionError::state(&self.key, e))?;
+        host::var_set(&self.key, bytes)
+    }
+
+    pub fn clear(&self) -> Result<(), FunctionError> {
+        host::var_remove(&self.key)
+    }
+
+    fn migrate(&self, mut version: u32, mut data: Value) -> Result<Value, FunctionError> {
+        if version > self.version {
+            return Err(FunctionError::state(
+                &self.key,
+                format!("stored version {version} is newer than {}", self.version),
+            ));
+        }
+        while version < self.version {
+            let (_, migration) = self
+                .migrations
+                .iter()
+                .find(|(from, _)| *from == version)
+                .ok_or_else(|| {
+                    FunctionError::state(&self.key, format!("no migration from version {version}"))
+                })?;
+            data = migration(data).map_err(|e| {
+                FunctionError::state(&self.key, format!("migration from version {version}: {e}"))
+            })?;
+            version += 1;
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::host::mock;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Memory {
+        last_alert: Option<i64>,
+        count: u32,
+    }
+
+    fn store(key: &str, envelope: Value) {
+        mock::set_var(key, serde_json::to_vec(&envelope).unwrap());
+    }
+
+    fn stored(key: &str) -> Value {
+        serde_json::from_slice(&mock::var(key).unwrap()).unwrap()
+    }
+
+    /// v1 named the field `last_alert_ts`, v2 added `count`.
+    fn memory_v3() -> State<Memory> {
+        State::<Memory>::new("memory", 3)
+            .with_migration(1, |mut v| {
+                v["last_alert"] = v["last_alert_ts"].take();
+                Ok(v)
+            })
+            .with_migration(2, |mut v| {
+                v["count"] = json!(0);
+                Ok(v)
+            })
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        mock::clear_vars();
+        let state = State::<Memory>::new("memory", 1);
+        assert_eq!(state.load(), Ok(None));
+        assert_eq!(state.load_or_default(), Ok(Memory::default()));
+
+        let memory = Memory {
+            last_alert: Some(1_700_000_000),
+            count: 2,
+        };
+        state.save(&memory).unwrap();
+        assert_eq!(
+            stored("memory"),
+            json!({ "version": 1, "data": { "last_alert": 1_700_000_000, "count": 2 } })
+        );
+        assert_eq!(state.load(), Ok(Some(memory)));
+
+        state.clear().unwrap();
+        assert_eq!(state.load(), Ok(None));
+    }
+
+    #[test]
+    fn applies_migrations_in_order() {
+        mock::clear_vars();
+        store(
+            "memory",
+            json!({ "version": 1, "data": { "last_alert_ts": 60 } }),
+        );
+        assert_eq!(
+            memory_v3().load(),
+            Ok(Some(Memory {
+                last_alert: Some(60),
+                count: 0,
+            }))
+        );
+
+        store(
+            "memory",
+            json!({ "version": 2, "data": { "last_alert": 120 } }),
+        );
+        assert_eq!(
+            memory_v3().load(),
+            Ok(Some(Memory {
+                last_alert: Some(120),
+                count: 0,
+            }))
+        );
+    }
+
+    #[test]
+    fn missing_migration() {
+        mock::clear_vars();
+        store("memory", json!({ "version": 0, "data": {} }));
+        assert_eq!(
+            memory_v3().load(),
+            Err(FunctionError::state(
+                "memory",
+                "no migration from version 0"
+            ))
+        );
+    }
+
+    #[test]
+    fn failing_migration() {
+        mock::clear_vars();
+        store("memory", json!({ "version": 1, "data": {} }));
+        let state = State::<Memory>::new("memory", 2)
+            .with_migration(1, |_| Err("unsupported layout".to_string()));
+        assert_eq!(
+            state.load(),
+            Err(FunctionError::state(
+                "memory",
+                "migration from version 1: unsupported layout"
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_newer_stored_version() {
+        mock::clear_vars();
+        store("memory", json!({ "version": 4, "data": {} }));
+        assert_eq!(
+            memory_v3().load(),
+            Err(FunctionError::state(
+                "memory",
+                "stored version 4 is newer than 3"
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_envelope_without_version() {
+        mock::clear_vars();
+        store("memory", json!({ "data": {} }));
+        assert_eq!(
+            memory_v3().load(),
+            Err(FunctionError::state("memory", "missing version"))
+        );
+    }
+}
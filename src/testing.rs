@@ -0,0 +1,105 @@
+//! Candle fixtures and float comparisons shared by unit tests.
+
+use exchange_outpost_abi::Candle;
+use rust_decimal::Decimal;
+use serde_json::{Value, json};
+
+use crate::harness::Fixture;
+use crate::indicators::Number;
+
+const LABEL: &str = "test";
+
+fn fixture(rows: &[(i64, [f64; 5])]) -> Fixture {
+    let candles: Vec<Value> = rows
+        .iter()
+        .map(|(timestamp, [open, high, low, close, volume])| {
+            json!({
+                "timestamp": timestamp,
+                "open": open,
+                "high": high,
+                "low": low,
+                "close": close,
+                "volume": volume,
+            })
+        })
+        .collect();
+    Fixture::new().with_candles(LABEL, candles)
+}
+
+fn minutes(rows: &[[f64; 5]]) -> Vec<(i64, [f64; 5])> {
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| (60 * i as i64, *row))
+        .collect()
+}
+
+/// Candles from `[open, high, low, close, volume]` rows, one minute apart.
+pub(crate) fn candles(rows: &[[f64; 5]]) -> Vec<Candle<f64>> {
+    candles_at(&minutes(rows))
+}
+
+pub(crate) fn decimal_candles(rows: &[[f64; 5]]) -> Vec<Candle<Decimal>> {
+    fixture(&minutes(rows))
+        .build()
+        .unwrap()
+        .get_candles_decimal(LABEL)
+        .unwrap()
+}
+
+/// Candles with explicit timestamps.
+pub(crate) fn candles_at(rows: &[(i64, [f64; 5])]) -> Vec<Candle<f64>> {
+    fixture(rows).build().unwrap().get_candles(LABEL).unwrap()
+}
+
+/// Candles whose open, high, low and close are all `price`.
+pub(crate) fn flat_candles(prices: &[f64]) -> Vec<Candle<f64>> {
+    let rows: Vec<[f64; 5]> = prices.iter().map(|&p| [p, p, p, p, 1.0]).collect();
+    candles(&rows)
+}
+
+pub(crate) fn decimals(values: &[f64]) -> Vec<Decimal> {
+    values
+        .iter()
+        .map(|&v| <Decimal as Number>::from_f64(v))
+        .collect()
+}
+
+pub(crate) fn to_f64<T: Number>(series: &[Option<T>]) -> Vec<Option<f64>> {
+    series.iter().map(|v| v.map(Number::to_f64)).collect()
+}
+
+/// Asserts both series have values in the same places, equal within `tolerance`.
+#[track_caller]
+pub(crate) fn assert_series(actual: &[Option<f64>], expected: &[Option<f64>], tolerance: f64) {
+    assert_eq!(actual.len(), expected.len(), "length of {actual:?}");
+    for (i, (a, e)) in actual.iter().zip(expected).enumerate() {
+        match (a, e) {
+            (Some(a), Some(e)) => assert!(
+                (a - e).abs() <= tolerance,
+                "index {i}: {a} != {e}\n actual: {actual:?}"
+            ),
+            (None, None) => {}
+            _ => panic!("index {i}: {a:?} != {e:?}\n actual: {actual:?}"),
+        }
+    }
+}
+
+#[track_caller]
+pub(crate) fn assert_near(actual: f64, expected: f64, tolerance: f64) {
+    assert!(
+        (actual - expected).abs() <= tolerance,
+        "{actual} != {expected}"
+    );
+}
+
+/// Number of leading `None`s.
+pub(crate) fn warm_up<T>(series: &[Option<T>]) -> usize {
+    series.iter().take_while(|v| v.is_none()).count()
+}
+
+/// `warm_up` leading `None`s followed by `values`.
+pub(crate) fn padded(warm_up: usize, values: &[f64]) -> Vec<Option<f64>> {
+    let mut series = vec![None; warm_up];
+    series.extend(values.iter().copied().map(Some));
+    series
+}
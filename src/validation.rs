@@ -0,0 +1,269 @@
+//! Pre-flight checks on the financial data passed to `run`, so a missing or
+//! malformed series is reported up front instead of failing deep inside user
+//! code.
+
+use std::fmt;
+
+use exchange_outpost_abi::FunctionArgs;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "issue", rename_all = "snake_case")]
+pub enum SeriesIssue {
+    Missing,
+    Empty,
+    /// Timestamp at `index` is not after the previous candle's.
+    Unsorted {
+        index: usize,
+    },
+    /// Candle at `index` has non-finite values, `high < low`, open/close
+    /// outside the high/low range, or negative volume.
+    Malformed {
+        index: usize,
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SeriesProblem {
+    pub label: String,
+    #[serde(flatten)]
+    pub issue: SeriesIssue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DataValidationError {
+    pub problems: Vec<SeriesProblem>,
+}
+
+impl fmt::Display for DataValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid financial data:")?;
+        for problem in &self.problems {
+            write!(f, " `{}` ", problem.label)?;
+            match &problem.issue {
+                SeriesIssue::Missing => write!(f, "is missing;")?,
+                SeriesIssue::Empty => write!(f, "has no candles;")?,
+                SeriesIssue::Unsorted { index } => {
+                    write!(f, "is not sorted by timestamp at candle {index};")?
+                }
+                SeriesIssue::Malformed { index, reason } => {
+                    write!(f, "candle {index} is malformed: {reason};")?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DataValidationError {}
+
+/// Checks that every label in `keys` has a non-empty, timestamp-sorted series
+/// of well-formed candles. All problems are collected, not just the first.
+pub fn validate_financial_data(
+    call_args: &FunctionArgs,
+    keys: &[&str],
+) -> Result<(), DataValidationError> {
+    let mut problems = Vec::new();
+    for &label in keys {
+        let issue = match call_args.get_candles(label) {
+            Err(_) => Some(SeriesIssue::Missing),
+            Ok(candles) if candles.is_empty() => Some(SeriesIssue::Empty),
+            Ok(candles) => check_series(
+                candles
+                    .iter()
+                    .map(|c| (c.timestamp, [c.open, c.high, c.low, c.close, c.volume])),
+            ),
+        };
+        if let Some(issue) = issue {
+            problems.push(SeriesProblem {
+                label: label.to_string(),
+                issue,
+            });
+        }
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(DataValidationError { problems })
+    }
+}
+
+fn check_series(candles: impl Iterator<Item = (i64, [f64; 5])>) -> Option<SeriesIssue> {
+    let mut previous = None;
+    for (index, (timestamp, [open, high, low, close, volume])) in candles.enumerate() {
+        let reason = if ![open, high, low, close, volume]
+            .iter()
+            .all(|v| v.is_finite())
+        {
+            Some("non-finite value")
+        } else if high < low {
+            Some("high is below low")
+        } else if open < low || open > high || close < low || close > high {
+            Some("open or close outside the high/low range")
+        } else if volume < 0.0 {
+            Some("negative volume")
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            return Some(SeriesIssue::Malformed {
+                index,
+                reason: reason.to_string(),
+            });
+        }
+        if previous.is_some_and(|previous| timestamp <= previous) {
+            return Some(SeriesIssue::Unsorted { index });
+        }
+        previous = Some(timestamp);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::harness::Fixture;
+
+    fn candle(timestamp: i64, [open, high, low, close, volume]: [f64; 5]) -> Value {
+        json!({
+            "timestamp": timestamp,
+            "open": open,
+            "high": high,
+            "low": low,
+            "close": close,
+            "volume": volume,
+        })
+    }
+
+    fn problem(label: &str, issue: SeriesIssue) -> SeriesProblem {
+        SeriesProblem {
+            label: label.to_string(),
+            issue,
+        }
+    }
+
+    fn malformed(index: usize, reason: &str) -> Option<SeriesIssue> {
+        Some(SeriesIssue::Malformed {
+            index,
+            reason: reason.to_string(),
+        })
+    }
+
+    #[test]
+    fn accepts_well_formed_series() {
+        let args = Fixture::new()
+            .with_candles(
+                "btc",
+                vec![
+                    candle(60, [10.0, 12.0, 9.0, 11.0, 5.0]),
+                    candle(120, [11.0, 11.0, 11.0, 11.0, 0.0]),
+                ],
+            )
+            .build()
+            .unwrap();
+        assert_eq!(validate_financial_data(&args, &["btc"]), Ok(()));
+    }
+
+    #[test]
+    fn collects_problems_across_labels() {
+        let args = Fixture::new()
+            .with_candles("empty", Vec::new())
+            .with_candles(
+                "unsorted",
+                vec![
+                    candle(120, [10.0, 12.0, 9.0, 11.0, 5.0]),
+                    candle(60, [10.0, 12.0, 9.0, 11.0, 5.0]),
+                ],
+            )
+            .with_candles(
+                "malformed",
+                vec![
+                    candle(60, [10.0, 12.0, 9.0, 11.0, 5.0]),
+                    candle(120, [10.0, 9.0, 12.0, 11.0, 5.0]),
+                ],
+            )
+            .with_candles("ok", vec![candle(60, [10.0, 12.0, 9.0, 11.0, 5.0])])
+            .build()
+            .unwrap();
+        let err =
+            validate_financial_data(&args, &["missing", "empty", "unsorted", "malformed", "ok"])
+                .unwrap_err();
+        assert_eq!(
+            err.problems,
+            vec![
+                problem("missing", SeriesIssue::Missing),
+                problem("empty", SeriesIssue::Empty),
+                problem("unsorted", SeriesIssue::Unsorted { index: 1 }),
+                problem(
+                    "malformed",
+                    SeriesIssue::Malformed {
+                        index: 1,
+                        reason: "high is below low".to_string(),
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            err.to_string(),
+            "invalid financial data: `missing` is missing; `empty` has no candles; \
+             `unsorted` is not sorted by timestamp at candle 1; \
+             `malformed` candle 1 is malformed: high is below low;"
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_timestamps() {
+        let candles = [
+            (60, [1.0, 1.0, 1.0, 1.0, 1.0]),
+            (60, [1.0, 1.0, 1.0, 1.0, 1.0]),
+        ];
+        assert_eq!(
+            check_series(candles.into_iter()),
+            Some(SeriesIssue::Unsorted { index: 1 })
+        );
+    }
+
+    #[test]
+    fn reports_each_malformed_candle_reason() {
+        let check = |values: [f64; 5]| check_series([(60, values)].into_iter());
+        assert_eq!(
+            check([f64::NAN, 1.0, 1.0, 1.0, 1.0]),
+            malformed(0, "non-finite value")
+        );
+        assert_eq!(
+            check([1.0, f64::INFINITY, 1.0, 1.0, 1.0]),
+            malformed(0, "non-finite value")
+        );
+        assert_eq!(
+            check([1.0, 1.0, 2.0, 1.0, 1.0]),
+            malformed(0, "high is below low")
+        );
+        assert_eq!(
+            check([3.0, 2.0, 1.0, 1.5, 1.0]),
+            malformed(0, "open or close outside the high/low range")
+        );
+        assert_eq!(
+            check([1.5, 2.0, 1.0, 0.5, 1.0]),
+            malformed(0, "open or close outside the high/low range")
+        );
+        assert_eq!(
+            check([1.5, 2.0, 1.0, 1.5, -1.0]),
+            malformed(0, "negative volume")
+        );
+    }
+
+    #[test]
+    fn malformed_candle_is_reported_before_ordering() {
+        let candles = [
+            (120, [1.0, 1.0, 1.0, 1.0, 1.0]),
+            (60, [1.0, 1.0, 1.0, 1.0, -1.0]),
+        ];
+        assert_eq!(
+            check_series(candles.into_iter()),
+            malformed(1, "negative volume")
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use rust_function_template::alerts::chat::ChatFormat;
+use rust_function_template::alerts::notifier::NotifierConfig;
+use rust_function_template::alerts::signing::{Signer, verify};
+use rust_function_template::alerts::template::EmailTemplate;
+use rust_function_template::alerts::{AlertEngine, Condition, Rule};
+use rust_function_template::harness::Fixture;
+use rust_function_template::host::mock;
+use serde_json::{Value, json};
+
+/// One-minute candles closing at `closes`.
+fn fixture(closes: &[f64]) -> Fixture {
+    let candles = closes
+        .iter()
+        .enumerate()
+        .map(|(i, close)| {
+            json!({
+                "timestamp": 60 * (i as i64 + 1),
+                "open": close,
+                "high": close,
+                "low": close,
+                "close": close,
+                "volume": 1.0,
+            })
+        })
+        .collect();
+    Fixture::new().with_candles("btc", candles)
+}
+
+fn engine() -> AlertEngine {
+    AlertEngine::new(vec![
+        Rule::new("above-100", "btc", Condition::CrossAbove(100.0))
+            .webhook("/alerts")
+            .email("desk@example.com"),
+    ])
+    .unwrap()
+}
+
+#[test]
+fn fires_once_per_cross() {
+    mock::clear_vars();
+    let engine = engine();
+
+    let result = fixture(&[90.0, 95.0, 105.0])
+        .run_recorded_with(|args| engine.run(args))
+        .unwrap();
+    assert_eq!(result.output.len(), 1);
+    assert_eq!(result.output[0].rule, "above-100");
+    assert_eq!(result.output[0].timestamp, 180);
+    let webhooks = result.webhooks();
+    assert_eq!(webhooks.len(), 1);
+    assert_eq!(webhooks[0].0, "/alerts");
+    let payload: Value = serde_json::from_str(webhooks[0].1).unwrap();
+    assert_eq!(payload["rule"], "above-100");
+    assert_eq!(payload["price"], 105.0);
+    assert_eq!(
+        result.emails(),
+        vec![(
+            "desk@example.com",
+            "above-100: btc crossed above 100 (price 105 at 1970-01-01T00:03:00Z)"
+        )]
+    );
+
+    // Still above the level: no new alert while the condition holds.
+    for closes in [
+        &[90.0, 95.0, 105.0, 110.0][..],
+        &[90.0, 95.0, 105.0, 110.0, 120.0],
+    ] {
+        let result = fixture(closes)
+            .run_recorded_with(|args| engine.run(args))
+            .unwrap();
+        assert!(result.output.is_empty());
+        assert!(result.effects.is_empty());
+    }
+
+    // Back below, then above again.
+    let result = fixture(&[90.0, 95.0, 105.0, 110.0, 120.0, 95.0])
+        .run_recorded_with(|args| engine.run(args))
+        .unwrap();
+    assert!(result.effects.is_empty());
+    let result = fixture(&[90.0, 95.0, 105.0, 110.0, 120.0, 95.0, 101.0])
+        .run_recorded_with(|args| engine.run(args))
+        .unwrap();
+    assert_eq!(result.output.len(), 1);
+    assert_eq!(result.webhooks().len(), 1);
+    assert_eq!(result.emails().len(), 1);
+}
+
+#[test]
+fn condition_already_active_on_first_run_does_not_fire() {
+    mock::clear_vars();
+    let result = fixture(&[105.0, 110.0])
+        .run_recorded_with(|args| engine().run(args))
+        .unwrap();
+    assert!(result.output.is_empty());
+    assert!(result.effects.is_empty());
+}
+
+#[test]
+fn rejects_duplicate_rule_names() {
+    let rule = Rule::new("above-100", "btc", Condition::CrossAbove(100.0));
+    let err = AlertEngine::new(vec![rule.clone(), rule]).err().unwrap();
+    assert_eq!(err.code(), "invalid_argument");
+    assert_eq!(
+        err.to_string(),
+        "invalid arguments: duplicate alert rule name `above-100`"
+    );
+}
+
+#[test]
+fn failed_dispatch_keeps_the_alert_pending() {
+    mock::clear_vars();
+    let engine = AlertEngine::new(vec![
+        Rule::new("above-100", "btc", Condition::CrossAbove(100.0))
+            .webhook("/alerts")
+            .email_with("desk@example.com", EmailTemplate::text("{{missing}}")),
+    ])
+    .unwrap()
+    .with_notifier(NotifierConfig {
+        dedup_window: 3_600,
+        ..NotifierConfig::default()
+    });
+
+    let result = fixture(&[90.0, 105.0]).run_recorded_with(|args| engine.run(args));
+    assert!(result.is_err());
+    // The webhook went out and is remembered by the notifier, but the rule
+    // has not been marked active, so the next run retries the alert.
+    assert!(mock::var("alerts.notifier").is_some());
+    assert!(mock::var("alerts").is_none());
+}
+
+#[test]
+fn retry_on_the_same_candle_does_not_notify_twice() {
+    mock::clear_vars();
+    let failing = AlertEngine::new(vec![
+        Rule::new("above-100", "btc", Condition::CrossAbove(100.0))
+            .webhook("/alerts")
+            .email_with("desk@example.com", EmailTemplate::text("{{missing}}")),
+    ])
+    .unwrap();
+    let candles = fixture(&[90.0, 105.0]);
+    assert!(candles.run_recorded_with(|args| failing.run(args)).is_err());
+
+    // The default notifier has no dedup window; the webhook that already
+    // went out is still not sent again.
+    let fixed = AlertEngine::new(vec![
+        Rule::new("above-100", "btc", Condition::CrossAbove(100.0))
+            .webhook("/alerts")
+            .email("desk@example.com"),
+    ])
+    .unwrap();
+    let result = candles.run_recorded_with(|args| fixed.run(args)).unwrap();
+    assert_eq!(result.output.len(), 1);
+    assert!(result.webhooks().is_empty());
+    assert_eq!(result.emails().len(), 1);
+    assert!(mock::var("alerts").is_some());
+}
+
+#[test]
+fn signs_only_json_webhooks() {
+    mock::clear_vars();
+    let engine = AlertEngine::new(vec![
+        Rule::new("above-100", "btc", Condition::CrossAbove(100.0))
+            .webhook("/alerts")
+            .webhook_as("/slack", ChatFormat::Slack),
+    ])
+    .unwrap()
+    .with_signer(Some(Signer::new("secret")));
+
+    let result = fixture(&[90.0, 105.0])
+        .run_recorded_with(|args| engine.run(args))
+        .unwrap();
+    let webhooks = result.webhooks();
+    assert_eq!(webhooks.len(), 2);
+
+    let (path, body) = webhooks[0];
+    assert_eq!(path, "/alerts");
+    let payload = verify(body, b"secret", 120, Some(60)).unwrap();
+    let payload: Value = serde_json::from_str(&payload).unwrap();
+    assert_eq!(payload["rule"], "above-100");
+
+    let (path, body) = webhooks[1];
+    assert_eq!(path, "/slack");
+    let slack: Value = serde_json::from_str(body).unwrap();
+    assert_eq!(slack, ChatFormat::Slack.render(&result.output[0]));
+}
+
+#[test]
+fn rejects_zero_candle_lookbacks() {
+    for condition in [
+        Condition::NewHigh { bars: 0 },
+        Condition::NewLow { bars: 0 },
+        Condition::PercentMove {
+            percent: 1.0,
+            bars: 0,
+        },
+    ] {
+        let err = AlertEngine::new(vec![Rule::new("breakout", "btc", condition)])
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "invalid arguments: alert rule `breakout` must look back at least one candle"
+        );
+    }
+}
+
+#[test]
+fn unknown_label_is_missing_data() {
+    mock::clear_vars();
+    let engine = AlertEngine::new(vec![
+        Rule::new("above-100", "btc", Condition::CrossAbove(100.0)).webhook("/alerts"),
+        Rule::new("eth-above", "eth", Condition::CrossAbove(100.0)),
+        Rule::new("eth-below", "eth", Condition::CrossBelow(50.0)),
+    ])
+    .unwrap();
+
+    let err = engine
+        .run(&fixture(&[90.0, 105.0]).build().unwrap())
+        .unwrap_err();
+    assert_eq!(err.code(), "missing_data");
+    assert_eq!(err.to_string(), "invalid financial data: `eth` is missing;");
+    // Nothing was evaluated, so the btc cross still fires once eth arrives.
+    assert!(mock::var("alerts").is_none());
+}
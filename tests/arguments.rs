@@ -0,0 +1,196 @@
+use rust_function_template::arguments::{ArgumentError, CallArguments};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Average {
+    Sma,
+    Ema,
+}
+
+#[derive(Debug, PartialEq, Deserialize, CallArguments)]
+#[serde(rename_all = "camelCase")]
+struct Arguments {
+    /// Number of periods
+    #[argument(minimum = 1, maximum = 200, default = 20)]
+    period: u32,
+    #[argument(enum = ["sma", "ema"], default = "sma")]
+    average_kind: Average,
+    #[serde(rename = "limit")]
+    threshold: Option<f64>,
+    show_bands: bool,
+    levels: Vec<f64>,
+}
+
+fn error(argument: &str, message: &str) -> ArgumentError {
+    ArgumentError {
+        argument: Some(argument.to_string()),
+        message: message.to_string(),
+    }
+}
+
+#[test]
+fn schema_follows_serde_names_and_types() {
+    assert_eq!(
+        Arguments::schema(),
+        json!({
+            "type": "object",
+            "properties": {
+                "period": {
+                    "type": ["integer", "string"],
+                    "description": "Number of periods",
+                    "minimum": 1,
+                    "maximum": 200,
+                    "default": 20,
+                },
+                "averageKind": {
+                    "type": "string",
+                    "enum": ["sma", "ema"],
+                    "default": "sma",
+                },
+                "limit": { "type": ["number", "string"] },
+                "showBands": { "type": ["boolean", "string"] },
+                "levels": {
+                    "type": "array",
+                    "items": { "type": ["number", "string"] },
+                },
+            },
+            "required": ["showBands", "levels"],
+        })
+    );
+}
+
+#[test]
+fn applies_defaults() {
+    let arguments = Arguments::from_value(json!({ "showBands": true, "levels": [] })).unwrap();
+    assert_eq!(
+        arguments,
+        Arguments {
+            period: 20,
+            average_kind: Average::Sma,
+            threshold: None,
+            show_bands: true,
+            levels: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn coerces_strings() {
+    let arguments = Arguments::from_value(json!({
+        "period": "50",
+        "averageKind": "ema",
+        "limit": "0.5",
+        "showBands": "false",
+        "levels": ["1.5", 2],
+    }))
+    .unwrap();
+    assert_eq!(
+        arguments,
+        Arguments {
+            period: 50,
+            average_kind: Average::Ema,
+            threshold: Some(0.5),
+            show_bands: false,
+            levels: vec![1.5, 2.0],
+        }
+    );
+}
+
+#[test]
+fn rejects_unparseable_strings() {
+    let err = Arguments::from_value(json!({ "period": "abc", "showBands": true, "levels": [] }));
+    assert_eq!(err.unwrap_err(), error("period", "`abc` is not an integer"));
+}
+
+#[test]
+fn checks_minimum_and_maximum() {
+    let below = Arguments::from_value(json!({ "period": 0, "showBands": true, "levels": [] }));
+    assert_eq!(
+        below.unwrap_err(),
+        error("period", "0 is below the minimum 1")
+    );
+
+    let above = Arguments::from_value(json!({ "period": "201", "showBands": true, "levels": [] }));
+    assert_eq!(
+        above.unwrap_err(),
+        error("period", "201 is above the maximum 200")
+    );
+}
+
+#[test]
+fn checks_enum() {
+    let err = Arguments::from_value(json!({
+        "averageKind": "wma",
+        "showBands": true,
+        "levels": [],
+    }));
+    assert_eq!(
+        err.unwrap_err(),
+        error("averageKind", r#""wma" is not one of ["sma","ema"]"#)
+    );
+}
+
+#[test]
+fn reports_missing_required_argument() {
+    let err = Arguments::from_value(json!({ "levels": [1.0] }));
+    assert_eq!(
+        err.unwrap_err(),
+        error("showBands", "missing required argument")
+    );
+}
+
+#[test]
+fn rejects_non_object_arguments() {
+    let err = Arguments::from_value(json!([1, 2]));
+    assert_eq!(
+        err.unwrap_err(),
+        ArgumentError {
+            argument: None,
+            message: "call arguments must be an object".to_string(),
+        }
+    );
+}
+
+#[derive(Debug, PartialEq, Deserialize, CallArguments)]
+struct Lists {
+    #[argument(enum = ["sma", "ema"])]
+    averages: Vec<String>,
+    #[serde(default)]
+    verbose: bool,
+}
+
+#[test]
+fn serde_default_fields_are_not_required() {
+    assert_eq!(Lists::schema()["required"], json!(["averages"]));
+    assert_eq!(
+        Lists::from_value(json!({ "averages": [] })).unwrap(),
+        Lists {
+            averages: Vec::new(),
+            verbose: false,
+        }
+    );
+}
+
+#[test]
+fn checks_enum_on_each_list_element() {
+    assert_eq!(
+        Lists::schema()["properties"]["averages"],
+        json!({
+            "type": "array",
+            "items": { "type": "string", "enum": ["sma", "ema"] },
+        })
+    );
+    assert_eq!(
+        Lists::from_value(json!({ "averages": ["ema", "sma", "ema"] }))
+            .unwrap()
+            .averages,
+        vec!["ema", "sma", "ema"]
+    );
+    let err = Lists::from_value(json!({ "averages": ["sma", "wma"] }));
+    assert_eq!(
+        err.unwrap_err(),
+        error("averages", r#""wma" is not one of ["sma","ema"]"#)
+    );
+}
@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use rust_function_template::alerts::Alert;
+use rust_function_template::alerts::chat::ChatFormat;
+use serde_json::Value;
+
+fn alert() -> Alert {
+    Alert {
+        rule: "btc-breakout".to_string(),
+        label: "btc".to_string(),
+        condition: "crossed above 100000".to_string(),
+        timestamp: 1_700_000_000,
+        price: 100_250.5,
+        indicators: BTreeMap::from([("rsi".to_string(), 71.25)]),
+    }
+}
+
+/// Rule and label with characters the chat formats must escape.
+fn escaped_alert() -> Alert {
+    Alert {
+        rule: "dip <5% & *fast*".to_string(),
+        label: "<btc & eth>_*".to_string(),
+        condition: "crossed below 100".to_string(),
+        timestamp: 1_700_000_000,
+        price: 95.5,
+        indicators: BTreeMap::from([("macd_signal".to_string(), -1.5)]),
+    }
+}
+
+fn assert_golden(format: ChatFormat, alert: &Alert, fixture: &str) {
+    let path = format!("tests/fixtures/chat/{fixture}.json");
+    let expected: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(format.render(alert), expected, "{path}");
+}
+
+#[test]
+fn slack_matches_golden() {
+    assert_golden(ChatFormat::Slack, &alert(), "slack");
+}
+
+#[test]
+fn discord_matches_golden() {
+    assert_golden(ChatFormat::Discord, &alert(), "discord");
+}
+
+#[test]
+fn teams_matches_golden() {
+    assert_golden(ChatFormat::Teams, &alert(), "teams");
+}
+
+#[test]
+fn telegram_matches_golden() {
+    let format = ChatFormat::from_argument("telegram", Some("-1001234567890")).unwrap();
+    assert_golden(format, &alert(), "telegram");
+}
+
+#[test]
+fn slack_escapes_control_characters() {
+    assert_golden(ChatFormat::Slack, &escaped_alert(), "slack_escaped");
+}
+
+#[test]
+fn discord_escapes_markdown() {
+    assert_golden(ChatFormat::Discord, &escaped_alert(), "discord_escaped");
+}
+
+#[test]
+fn telegram_escapes_html() {
+    let format = ChatFormat::from_argument("telegram", Some("-1001234567890")).unwrap();
+    assert_golden(format, &escaped_alert(), "telegram_escaped");
+}
+
+#[test]
+fn telegram_requires_chat_id() {
+    assert!(ChatFormat::from_argument("telegram", None).is_err());
+}
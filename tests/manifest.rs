@@ -0,0 +1,12 @@
+use rust_function_template::manifest::manifest;
+use serde_json::Value;
+
+#[test]
+fn committed_manifest_is_up_to_date() {
+    let committed: Value = serde_json::from_str(include_str!("../manifest.json")).unwrap();
+    assert_eq!(
+        manifest().to_value(),
+        committed,
+        "manifest.json is out of date, run `cargo xtask manifest`"
+    );
+}
@@ -0,0 +1,170 @@
+use rust_function_template::alerts::notifier::{Delivery, Notifier, NotifierConfig};
+use rust_function_template::harness::{Fixture, RunResult};
+use rust_function_template::host::mock;
+use serde_json::Value;
+
+const CROSS: &str = "crossed above 100";
+
+/// Runs `f` against the notifier persisted by previous runs, then saves it.
+fn run(
+    config: NotifierConfig,
+    f: impl FnOnce(&mut Notifier) -> Vec<Delivery>,
+) -> RunResult<Vec<Delivery>> {
+    Fixture::new()
+        .with_candles("btc", Vec::new())
+        .run_recorded_with(|_| {
+            let mut notifier = Notifier::load(config)?;
+            let deliveries = f(&mut notifier);
+            notifier.save()?;
+            Ok(deliveries)
+        })
+        .unwrap()
+}
+
+fn webhook(notifier: &mut Notifier, rule: &str, condition: &str, now: i64, path: &str) -> Delivery {
+    notifier
+        .webhook(rule, condition, now, path, &format!("{rule} at {now}"))
+        .unwrap()
+}
+
+#[test]
+fn cooldown_is_per_rule_and_target() {
+    mock::clear_vars();
+    let config = NotifierConfig {
+        cooldown: 300,
+        ..NotifierConfig::default()
+    };
+
+    let result = run(config, |n| vec![webhook(n, "a", CROSS, 0, "/alerts")]);
+    assert_eq!(result.output, vec![Delivery::Sent]);
+    assert_eq!(result.webhooks(), vec![("/alerts", "a at 0")]);
+
+    let result = run(config, |n| {
+        vec![
+            webhook(n, "a", CROSS, 240, "/alerts"),
+            webhook(n, "a", CROSS, 240, "/audit"),
+            webhook(n, "b", CROSS, 240, "/alerts"),
+            n.email("a", CROSS, 240, "desk@example.com", "body")
+                .unwrap(),
+        ]
+    });
+    assert_eq!(
+        result.output,
+        vec![
+            Delivery::CoolingDown,
+            Delivery::Sent,
+            Delivery::Sent,
+            Delivery::Sent
+        ]
+    );
+    assert_eq!(
+        result.webhooks(),
+        vec![("/audit", "a at 240"), ("/alerts", "b at 240")]
+    );
+
+    let result = run(config, |n| vec![webhook(n, "a", CROSS, 300, "/alerts")]);
+    assert_eq!(result.output, vec![Delivery::Sent]);
+}
+
+#[test]
+fn dedup_ignores_the_rendered_content() {
+    mock::clear_vars();
+    let config = NotifierConfig {
+        dedup_window: 600,
+        ..NotifierConfig::default()
+    };
+
+    run(config, |n| vec![webhook(n, "a", CROSS, 0, "/alerts")]);
+    let result = run(config, |n| {
+        vec![
+            // Same rule, target and condition; only the payload differs.
+            webhook(n, "a", CROSS, 60, "/alerts"),
+            webhook(n, "a", "crossed below 100", 60, "/alerts"),
+            webhook(n, "b", CROSS, 60, "/alerts"),
+        ]
+    });
+    assert_eq!(
+        result.output,
+        vec![Delivery::Duplicate, Delivery::Sent, Delivery::Sent]
+    );
+    assert_eq!(
+        result.webhooks(),
+        vec![("/alerts", "a at 60"), ("/alerts", "b at 60")]
+    );
+
+    let result = run(config, |n| vec![webhook(n, "a", CROSS, 600, "/alerts")]);
+    assert_eq!(result.output, vec![Delivery::Sent]);
+}
+
+#[test]
+fn rate_cap_counts_every_notification_in_the_window() {
+    mock::clear_vars();
+    let config = NotifierConfig {
+        max_per_window: 2,
+        window: 300,
+        ..NotifierConfig::default()
+    };
+
+    let result = run(config, |n| {
+        vec![
+            webhook(n, "a", CROSS, 0, "/alerts"),
+            webhook(n, "b", CROSS, 10, "/alerts"),
+            webhook(n, "c", CROSS, 20, "/alerts"),
+        ]
+    });
+    assert_eq!(
+        result.output,
+        vec![Delivery::Sent, Delivery::Sent, Delivery::RateLimited]
+    );
+    assert_eq!(result.webhooks().len(), 2);
+
+    // The notification at 0 has left the window, the one at 10 has not.
+    let result = run(config, |n| {
+        vec![
+            webhook(n, "c", CROSS, 300, "/alerts"),
+            webhook(n, "d", CROSS, 300, "/alerts"),
+        ]
+    });
+    assert_eq!(result.output, vec![Delivery::Sent, Delivery::RateLimited]);
+}
+
+#[test]
+fn save_drops_records_outside_every_window() {
+    mock::clear_vars();
+    let config = NotifierConfig {
+        cooldown: 100,
+        dedup_window: 200,
+        ..NotifierConfig::default()
+    };
+    run(config, |n| {
+        vec![
+            webhook(n, "a", CROSS, 0, "/alerts"),
+            webhook(n, "b", CROSS, 150, "/alerts"),
+            webhook(n, "c", CROSS, 250, "/alerts"),
+        ]
+    });
+
+    let stored: Value = serde_json::from_slice(&mock::var("notifier").unwrap()).unwrap();
+    let rules: Vec<&str> = stored["data"]["sent"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|record| record["rule"].as_str().unwrap())
+        .collect();
+    assert_eq!(rules, vec!["b", "c"]);
+}
+
+#[test]
+fn repeat_at_the_same_timestamp_is_dropped_without_a_dedup_window() {
+    mock::clear_vars();
+    let config = NotifierConfig::default();
+
+    let result = run(config, |n| vec![webhook(n, "a", CROSS, 60, "/alerts")]);
+    assert_eq!(result.output, vec![Delivery::Sent]);
+    let result = run(config, |n| vec![webhook(n, "a", CROSS, 60, "/alerts")]);
+    assert_eq!(result.output, vec![Delivery::Duplicate]);
+    assert!(result.effects.is_empty());
+
+    let result = run(config, |n| vec![webhook(n, "a", CROSS, 120, "/alerts")]);
+    assert_eq!(result.output, vec![Delivery::Sent]);
+}
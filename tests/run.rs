@@ -0,0 +1,76 @@
+use rust_function_template::harness::Fixture;
+use rust_function_template::host::{self, ScheduledEffect};
+
+#[test]
+fn run_with_csv_fixture() {
+    let fixture = Fixture::new()
+        .with_candles_csv_file("symbol_data", "tests/fixtures/candles.csv")
+        .unwrap();
+
+    fixture.run().unwrap();
+}
+
+#[test]
+fn template_schedules_nothing() {
+    let result = Fixture::new()
+        .with_candles_csv_file("symbol_data", "tests/fixtures/candles.csv")
+        .unwrap()
+        .run_recorded()
+        .unwrap();
+
+    assert!(result.effects.is_empty());
+}
+
+#[test]
+fn records_effects_in_order() {
+    let result = Fixture::new()
+        .with_candles_csv_file("symbol_data", "tests/fixtures/candles.csv")
+        .unwrap()
+        .run_recorded_with(|_| {
+            host::schedule_webhook("/alerts", r#"{"price":101.8}"#)?;
+            host::schedule_email("desk@example.com", "Subject: BTC\n\nPrice 101.8")?;
+            host::schedule_webhook("/audit", "second")?;
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(
+        result.effects,
+        vec![
+            ScheduledEffect::Webhook {
+                path: "/alerts".to_string(),
+                payload: r#"{"price":101.8}"#.to_string(),
+            },
+            ScheduledEffect::Email {
+                to: "desk@example.com".to_string(),
+                body: "Subject: BTC\n\nPrice 101.8".to_string(),
+            },
+            ScheduledEffect::Webhook {
+                path: "/audit".to_string(),
+                payload: "second".to_string(),
+            },
+        ]
+    );
+    assert_eq!(
+        result.webhooks(),
+        vec![("/alerts", r#"{"price":101.8}"#), ("/audit", "second")]
+    );
+    assert_eq!(
+        result.emails(),
+        vec![("desk@example.com", "Subject: BTC\n\nPrice 101.8")]
+    );
+}
+
+#[test]
+fn effects_scheduled_before_a_run_are_not_recorded() {
+    host::schedule_webhook("/stale", "{}").unwrap();
+
+    let result = Fixture::new()
+        .with_candles_csv_file("symbol_data", "tests/fixtures/candles.csv")
+        .unwrap()
+        .run_recorded_with(|_| host::schedule_email("desk@example.com", "fresh"))
+        .unwrap();
+
+    assert!(result.webhooks().is_empty());
+    assert_eq!(result.emails(), vec![("desk@example.com", "fresh")]);
+}
@@ -0,0 +1,96 @@
+use rust_function_template::alerts::signing::{SignedEnvelope, Signer, VerifyError, verify};
+
+const PAYLOAD: &str = r#"{"rule":"a"}"#;
+const TIMESTAMP: i64 = 1_700_000_000;
+
+fn signed(secret: &str) -> String {
+    Signer::new(secret).sign(PAYLOAD, TIMESTAMP)
+}
+
+fn envelope(body: &str) -> SignedEnvelope {
+    serde_json::from_str(body).unwrap()
+}
+
+#[test]
+fn signature_is_hex_hmac_sha256_of_timestamp_and_payload() {
+    assert_eq!(
+        envelope(&signed("secret")),
+        SignedEnvelope {
+            payload: PAYLOAD.to_string(),
+            timestamp: TIMESTAMP,
+            signature: "2de2962c36acb24e1cfef9f226c796cb26d511b692f2c9dbf527820f604f1e13"
+                .to_string(),
+        }
+    );
+}
+
+#[test]
+fn round_trip() {
+    assert_eq!(
+        verify(&signed("secret"), b"secret", TIMESTAMP, None),
+        Ok(PAYLOAD.to_string())
+    );
+    assert_eq!(
+        verify(&signed("secret"), b"secret", TIMESTAMP + 60, Some(60)),
+        Ok(PAYLOAD.to_string())
+    );
+}
+
+#[test]
+fn rejects_tampered_payload_and_timestamp() {
+    let mut tampered = envelope(&signed("secret"));
+    tampered.payload = r#"{"rule":"b"}"#.to_string();
+    let body = serde_json::to_string(&tampered).unwrap();
+    assert_eq!(
+        verify(&body, b"secret", TIMESTAMP, None),
+        Err(VerifyError::BadSignature)
+    );
+
+    let mut tampered = envelope(&signed("secret"));
+    tampered.timestamp += 1;
+    let body = serde_json::to_string(&tampered).unwrap();
+    assert_eq!(
+        verify(&body, b"secret", TIMESTAMP, None),
+        Err(VerifyError::BadSignature)
+    );
+}
+
+#[test]
+fn rejects_wrong_secret() {
+    assert_eq!(
+        verify(&signed("other"), b"secret", TIMESTAMP, None),
+        Err(VerifyError::BadSignature)
+    );
+}
+
+#[test]
+fn rejects_malformed_envelopes() {
+    let with_signature = |signature: &str| {
+        let mut envelope = envelope(&signed("secret"));
+        envelope.signature = signature.to_string();
+        serde_json::to_string(&envelope).unwrap()
+    };
+    for signature in ["not hex", "abc", "zz"] {
+        assert_eq!(
+            verify(&with_signature(signature), b"secret", TIMESTAMP, None),
+            Err(VerifyError::Malformed("signature is not hex".to_string())),
+            "{signature}"
+        );
+    }
+    assert!(matches!(
+        verify(PAYLOAD, b"secret", TIMESTAMP, None),
+        Err(VerifyError::Malformed(_))
+    ));
+}
+
+#[test]
+fn rejects_envelopes_outside_the_tolerance() {
+    assert_eq!(
+        verify(&signed("secret"), b"secret", TIMESTAMP + 61, Some(60)),
+        Err(VerifyError::Expired { age: 61 })
+    );
+    assert_eq!(
+        verify(&signed("secret"), b"secret", TIMESTAMP - 61, Some(60)),
+        Err(VerifyError::Expired { age: -61 })
+    );
+}
@@ -0,0 +1,56 @@
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+
+mod manifest;
+#[cfg(feature = "runner")]
+mod runner;
+#[cfg_attr(not(feature = "runner"), allow(dead_code))]
+mod schema;
+
+const USAGE: &str = "usage:
+    cargo xtask run <function_args.json> [--wasm <path>] [--manifest <path>] [--config <key=value>]...
+    cargo xtask manifest [--check] [--path <path>]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        #[cfg(feature = "runner")]
+        Some("run") => runner::run(&args[1..]),
+        #[cfg(not(feature = "runner"))]
+        Some("run") => {
+            Err("`xtask run` needs the `runner` feature, use `cargo xtask run`".to_string())
+        }
+        Some("manifest") => manifest::run(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+pub(crate) fn read_file(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))
+}
+
+/// Splits `args` into positional arguments and `--flag value` pairs.
+pub(crate) fn parse_flags(args: &[String]) -> Result<(Vec<&str>, Vec<(&str, &str)>), String> {
+    let mut positional = Vec::new();
+    let mut flags = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if let Some(flag) = arg.strip_prefix("--") {
+            let value = iter
+                .next()
+                .ok_or_else(|| format!("missing value for --{flag}"))?;
+            flags.push((flag, value.as_str()));
+        } else {
+            positional.push(arg.as_str());
+        }
+    }
+    Ok((positional, flags))
+}
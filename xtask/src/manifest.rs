@@ -0,0 +1,46 @@
+//! Writes `manifest.json` from the function crate, or with `--check` fails
+//! when the committed manifest is out of date.
+
+use std::fs;
+
+use serde_json::Value;
+
+use crate::{parse_flags, read_file};
+
+const MANIFEST_PATH: &str = "manifest.json";
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let check = args.iter().any(|arg| arg == "--check");
+    let args: Vec<String> = args
+        .iter()
+        .filter(|arg| *arg != "--check")
+        .cloned()
+        .collect();
+    let (positional, flags) = parse_flags(&args)?;
+    if !positional.is_empty() {
+        return Err(format!("unexpected arguments: {}", positional.join(" ")));
+    }
+    let path = flags
+        .iter()
+        .rev()
+        .find(|(flag, _)| *flag == "path")
+        .map_or(MANIFEST_PATH, |(_, value)| *value);
+
+    let manifest = rust_function_template::manifest::manifest();
+    if !check {
+        fs::write(path, manifest.to_json_pretty()).map_err(|e| format!("{path}: {e}"))?;
+        println!("wrote {path}");
+        return Ok(());
+    }
+
+    let committed: Value =
+        serde_json::from_str(&read_file(path)?).map_err(|e| format!("{path}: {e}"))?;
+    if committed != manifest.to_value() {
+        return Err(format!(
+            "{path} is out of date, run `cargo xtask manifest` and commit the result. Expected:\n{}",
+            manifest.to_json_pretty()
+        ));
+    }
+    println!("{path} is up to date");
+    Ok(())
+}
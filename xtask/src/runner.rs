@@ -0,0 +1,116 @@
+//! Loads the built `finfunc.wasm` through the Extism host SDK and calls `run`
+//! with a local `FunctionArgs` file, the same way the platform does.
+
+use extism::{Function, Manifest, PTR, Plugin, UserData, Wasm, host_fn};
+use serde_json::{Value, json};
+
+use crate::{parse_flags, read_file, schema};
+
+const DEFAULT_WASM: &str = "target/wasm32-unknown-unknown/release/rust_function_template.wasm";
+const DEFAULT_MANIFEST: &str = "manifest.json";
+
+host_fn!(schedule_webhook(effects: Vec<Value>; path: String, payload: String) {
+    let effects = effects.get()?;
+    effects.lock().unwrap().push(json!({ "webhook": { "path": path, "payload": payload } }));
+    Ok(())
+});
+
+host_fn!(schedule_email(effects: Vec<Value>; to: String, body: String) {
+    let effects = effects.get()?;
+    effects.lock().unwrap().push(json!({ "email": { "to": to, "body": body } }));
+    Ok(())
+});
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let (positional, flags) = parse_flags(args)?;
+    let [args_path] = positional[..] else {
+        return Err("expected exactly one FunctionArgs json file".to_string());
+    };
+    let flag = |name: &str, default: &'static str| {
+        flags
+            .iter()
+            .rev()
+            .find(|(flag, _)| *flag == name)
+            .map_or(default, |(_, value)| *value)
+    };
+    let wasm_path = flag("wasm", DEFAULT_WASM);
+    let manifest_path = flag("manifest", DEFAULT_MANIFEST);
+
+    let input = read_file(args_path)?;
+    let function_args: Value =
+        serde_json::from_str(&input).map_err(|e| format!("{args_path}: {e}"))?;
+    let manifest: Value = serde_json::from_str(&read_file(manifest_path)?)
+        .map_err(|e| format!("{manifest_path}: {e}"))?;
+    check_input(&manifest, &function_args)?;
+
+    let effects = UserData::new(Vec::<Value>::new());
+    let functions = [
+        Function::new(
+            "schedule_webhook",
+            [PTR, PTR],
+            [],
+            effects.clone(),
+            schedule_webhook,
+        ),
+        Function::new(
+            "schedule_email",
+            [PTR, PTR],
+            [],
+            effects.clone(),
+            schedule_email,
+        ),
+    ];
+    let mut plugin_manifest = Manifest::new([Wasm::file(wasm_path)]);
+    for (_, entry) in flags.iter().filter(|(flag, _)| *flag == "config") {
+        let (key, value) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("--config expects key=value, got `{entry}`"))?;
+        plugin_manifest = plugin_manifest.with_config_key(key, value);
+    }
+    let mut plugin = Plugin::new(plugin_manifest, functions, true)
+        .map_err(|e| format!("failed to load {wasm_path}: {e}"))?;
+    let output = plugin
+        .call::<&str, &str>("run", &input)
+        .map_err(|e| format!("run failed: {e}"))?
+        .to_string();
+
+    let output: Value =
+        serde_json::from_str(&output).map_err(|e| format!("output is not json: {e}"))?;
+    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+
+    let effects = effects.get().map_err(|e| e.to_string())?;
+    for effect in effects.lock().unwrap().iter() {
+        eprintln!("scheduled: {effect}");
+    }
+    Ok(())
+}
+
+/// Rejects input the platform would refuse: missing financial data keys and,
+/// when the manifest sets `enforce_schemas`, call arguments that do not match
+/// `call_arguments_schema`.
+fn check_input(manifest: &Value, function_args: &Value) -> Result<(), String> {
+    let mut errors: Vec<String> = manifest["financial_data_keys"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .filter(|key| function_args["tickers_data"].get(key).is_none())
+        .map(|key| format!("financial data key `{key}` is missing from the input"))
+        .collect();
+    if manifest["enforce_schemas"].as_bool() == Some(true) {
+        let call_arguments = match &function_args["call_arguments"] {
+            Value::Null => json!({}),
+            value => value.clone(),
+        };
+        errors.extend(schema::validate(
+            &manifest["call_arguments_schema"],
+            &call_arguments,
+            "call_arguments",
+        ));
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("input rejected:\n  {}", errors.join("\n  ")))
+    }
+}
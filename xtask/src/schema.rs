@@ -0,0 +1,140 @@
+//! The subset of JSON Schema used by `call_arguments_schema`, checked the way
+//! the platform does when `enforce_schemas` is set: no defaults and no
+//! coercion, just `type`, `enum`, `minimum`, `maximum`, `items`, `properties`
+//! and `required`.
+
+use serde_json::Value;
+
+/// Returns every violation of `schema` in `value`, with `path` as the
+/// location prefix.
+pub fn validate(schema: &Value, value: &Value, path: &str) -> Vec<String> {
+    let mut errors = Vec::new();
+    if let Some(types) = types(schema)
+        && !types.iter().any(|t| has_type(value, t))
+    {
+        errors.push(format!(
+            "{path}: expected {}, got {value}",
+            types.join(" or ")
+        ));
+        return errors;
+    }
+    if let Some(allowed) = schema["enum"].as_array()
+        && !allowed.contains(value)
+    {
+        errors.push(format!(
+            "{path}: {value} is not one of {}",
+            Value::Array(allowed.clone())
+        ));
+    }
+    if let Some(n) = value.as_f64() {
+        if let Some(minimum) = schema["minimum"].as_f64()
+            && n < minimum
+        {
+            errors.push(format!("{path}: {n} is below the minimum {minimum}"));
+        }
+        if let Some(maximum) = schema["maximum"].as_f64()
+            && n > maximum
+        {
+            errors.push(format!("{path}: {n} is above the maximum {maximum}"));
+        }
+    }
+    if let (Some(items), Value::Array(values)) = (schema.get("items"), value) {
+        for (i, item) in values.iter().enumerate() {
+            errors.extend(validate(items, item, &format!("{path}[{i}]")));
+        }
+    }
+    if let Value::Object(fields) = value {
+        for required in schema["required"].as_array().into_iter().flatten() {
+            if let Some(name) = required.as_str()
+                && !fields.contains_key(name)
+            {
+                errors.push(format!("{path}.{name}: missing required property"));
+            }
+        }
+        if let Some(properties) = schema["properties"].as_object() {
+            for (name, property) in properties {
+                if let Some(field) = fields.get(name) {
+                    errors.extend(validate(property, field, &format!("{path}.{name}")));
+                }
+            }
+        }
+    }
+    errors
+}
+
+fn types(schema: &Value) -> Option<Vec<&str>> {
+    match &schema["type"] {
+        Value::String(t) => Some(vec![t.as_str()]),
+        Value::Array(types) => Some(types.iter().filter_map(Value::as_str).collect()),
+        _ => None,
+    }
+}
+
+fn has_type(value: &Value, json_type: &str) -> bool {
+    match json_type {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::validate;
+
+    fn schema() -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "period": { "type": ["integer", "string"], "minimum": 1, "maximum": 200 },
+                "kind": { "type": "string", "enum": ["sma", "ema"] },
+                "levels": { "type": "array", "items": { "type": ["number", "string"] } }
+            },
+            "required": ["period"]
+        })
+    }
+
+    #[test]
+    fn accepts_valid_arguments() {
+        let args = json!({ "period": 20, "kind": "ema", "levels": [1.5, "2"] });
+        assert!(validate(&schema(), &args, "call_arguments").is_empty());
+    }
+
+    fn errors(args: serde_json::Value) -> Vec<String> {
+        let mut errors = validate(&schema(), &args, "call_arguments");
+        errors.sort();
+        errors
+    }
+
+    #[test]
+    fn reports_every_violation() {
+        assert_eq!(
+            errors(json!({ "period": 0, "kind": "wma", "levels": [true] })),
+            vec![
+                "call_arguments.kind: \"wma\" is not one of [\"sma\",\"ema\"]",
+                "call_arguments.levels[0]: expected number or string, got true",
+                "call_arguments.period: 0 is below the minimum 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_missing_required_and_wrong_types() {
+        assert_eq!(
+            errors(json!({ "kind": 3 })),
+            vec![
+                "call_arguments.kind: expected string, got 3",
+                "call_arguments.period: missing required property",
+            ]
+        );
+    }
+}